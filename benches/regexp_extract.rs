@@ -1,10 +1,13 @@
 use std::sync::Arc;
 
 use criterion::{Criterion, criterion_group, criterion_main};
-use datafusion::arrow::array::{ArrayRef, Int32Array, LargeStringArray, StringArray};
-use datafusion::arrow::datatypes::DataType;
-use datafusion_regexp_extract_udf::InvalidPatternMode;
+use datafusion::arrow::array::{ArrayRef, Int32Array, Int64Array, LargeStringArray, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
 use datafusion_regexp_extract_udf::kernel::{run_large_utf8_utf8, run_utf8_utf8};
+use datafusion_regexp_extract_udf::{InvalidPatternMode, regexp_extract_udf};
 use std::hint::black_box;
 
 fn bench_utf8_scalar_pattern(c: &mut Criterion) {
@@ -88,6 +91,37 @@ fn bench_largeutf8_scalar_pattern(c: &mut Criterion) {
     });
 }
 
+fn bench_utf8_column_pattern_shuffled(c: &mut Criterion) {
+    let n = 20_000;
+    let strings = StringArray::from(vec![Some("key=100-200"); n]);
+    let strings_ref = &strings;
+    // 16 distinct patterns in scrambled order; exercises grouping by pattern value
+    let pats = (0..16)
+        .map(|k| format!(r"(\d+)-(\d+){}?", "x".repeat(k)))
+        .collect::<Vec<_>>();
+    let patterns = StringArray::from(
+        (0..n)
+            .map(|i| Some(pats[(i * 7919) % pats.len()].as_str()))
+            .collect::<Vec<_>>(),
+    );
+    let idx = Int32Array::from(vec![Some(1)]);
+    c.bench_function("utf8 / column pattern (16 shuffled) / scalar idx", |b| {
+        b.iter(|| {
+            let out = run_utf8_utf8(
+                black_box(strings_ref),
+                black_box(&patterns),
+                None,
+                Some(&idx),
+                &DataType::Utf8,
+                8,
+                InvalidPatternMode::Error,
+            )
+            .unwrap();
+            black_box(out);
+        });
+    });
+}
+
 fn bench_utf8_unique_patterns_miss(c: &mut Criterion) {
     let n = 20_000;
     let strings = StringArray::from((0..n).map(|i| Some(format!("x{i}"))).collect::<Vec<_>>());
@@ -196,14 +230,320 @@ fn bench_utf8_heavy_alternation(c: &mut Criterion) {
     });
 }
 
+fn bench_utf8_low_selectivity_literal(c: &mut Criterion) {
+    let n = 20_000;
+    // ~1% of rows carry the required literal; the rest are skipped by the prefilter
+    let strings = StringArray::from(
+        (0..n)
+            .map(|i| {
+                if i % 100 == 0 {
+                    Some(format!("GET /api/items?user_id={i}&page=2 HTTP/1.1"))
+                } else {
+                    Some(format!("GET /static/asset-{i}.js?v=3 HTTP/1.1"))
+                }
+            })
+            .collect::<Vec<_>>(),
+    );
+    let strings_ref = &strings;
+    let pat = StringArray::from(vec![Some(r"user_id=(\d+)")]);
+    let idx = Int32Array::from(vec![Some(1)]);
+    c.bench_function("utf8 / low-selectivity literal / scalar pat+idx", |b| {
+        b.iter(|| {
+            let out = run_utf8_utf8(
+                black_box(strings_ref),
+                black_box(&pat),
+                None,
+                Some(&idx),
+                &DataType::Utf8,
+                64,
+                InvalidPatternMode::Error,
+            )
+            .unwrap();
+            black_box(out);
+        });
+    });
+}
+
+/// Apache combined log lines with varying hosts, paths, statuses and sizes.
+fn apache_lines(n: usize) -> Vec<Option<String>> {
+    const METHODS: [&str; 4] = ["GET", "POST", "PUT", "DELETE"];
+    const STATUS: [u16; 5] = [200, 200, 304, 404, 500];
+    (0..n)
+        .map(|i| {
+            Some(format!(
+                r#"10.{}.{}.{} - - [10/Oct/2024:13:{:02}:{:02} +0000] "{} /api/v1/items/{}?page={} HTTP/1.1" {} {} "https://example.com/ref/{}" "Mozilla/5.0 (X11; Linux x86_64)""#,
+                i % 256,
+                (i / 7) % 256,
+                (i / 13) % 256,
+                i % 60,
+                (i / 60) % 60,
+                METHODS[i % METHODS.len()],
+                i,
+                i % 10,
+                STATUS[i % STATUS.len()],
+                (i * 37) % 50_000,
+                i % 100,
+            ))
+        })
+        .collect()
+}
+
+/// Runs `run_utf8_utf8` over fixed inputs; one Criterion function per call.
+fn bench_utf8(
+    c: &mut Criterion,
+    name: &str,
+    strings: &StringArray,
+    patterns: &StringArray,
+    idx: &Int64Array,
+    cache_size: usize,
+) {
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let out = run_utf8_utf8(
+                black_box(strings),
+                black_box(patterns),
+                Some(idx),
+                None,
+                &DataType::Utf8,
+                cache_size,
+                InvalidPatternMode::Error,
+            )
+            .unwrap();
+            black_box(out);
+        });
+    });
+}
+
+fn bench_utf8_log_formats(c: &mut Criterion) {
+    let n = 20_000;
+    let strings = StringArray::from(apache_lines(n));
+    let idx = Int64Array::from(vec![Some(1)]);
+
+    // Apache: status code after the quoted request line
+    let apache = StringArray::from(vec![Some(r#"" (\d{3}) \d+ ""#)]);
+    bench_utf8(c, "utf8 / apache log / status", &strings, &apache, &idx, 64);
+
+    // NGINX combined: the full field layout, pulling out the request path
+    let nginx = StringArray::from(vec![Some(
+        r#"^(\S+) - (\S+) \[([^\]]+)\] "(\S+) (\S+) (\S+)" (\d{3}) (\d+) "([^"]*)" "([^"]*)"$"#,
+    )]);
+    let path = Int64Array::from(vec![Some(5)]);
+    bench_utf8(
+        c,
+        "utf8 / nginx log / full layout path",
+        &strings,
+        &nginx,
+        &path,
+        64,
+    );
+}
+
+fn bench_utf8_high_cardinality_patterns(c: &mut Criterion) {
+    let n = 20_000;
+    let strings = StringArray::from(vec![Some("key=100-200"); n]);
+    // 4000 distinct patterns cycling: too many to group, more than the cache holds
+    let distinct = 4_000;
+    let patterns = StringArray::from(
+        (0..n)
+            .map(|i| Some(format!(r"key=(\d+)-(\d+)|k{}", i % distinct)))
+            .collect::<Vec<_>>(),
+    );
+    let idx = Int64Array::from(vec![Some(1)]);
+    bench_utf8(
+        c,
+        "utf8 / 4000 cycling patterns / cache 64",
+        &strings,
+        &patterns,
+        &idx,
+        64,
+    );
+    bench_utf8(
+        c,
+        "utf8 / 4000 cycling patterns / cache 4096",
+        &strings,
+        &patterns,
+        &idx,
+        4_096,
+    );
+}
+
+fn bench_utf8_nullable(c: &mut Criterion) {
+    let n = 20_000;
+    // one NULL in ten rows takes the null-aware loop instead of the fast path
+    let strings = StringArray::from(
+        (0..n)
+            .map(|i| (i % 10 != 0).then(|| format!("id-{i}-x")))
+            .collect::<Vec<_>>(),
+    );
+    let pat = StringArray::from(vec![Some(r"id-(\d+)")]);
+    let idx = Int64Array::from(vec![Some(1)]);
+    bench_utf8(
+        c,
+        "utf8 / 10% null strings / scalar pat+idx",
+        &strings,
+        &pat,
+        &idx,
+        64,
+    );
+
+    let pats = StringArray::from(
+        (0..n)
+            .map(|i| (i % 10 != 5).then_some(r"id-(\d+)"))
+            .collect::<Vec<_>>(),
+    );
+    bench_utf8(
+        c,
+        "utf8 / 10% null patterns / column pat",
+        &strings,
+        &pats,
+        &idx,
+        64,
+    );
+}
+
+fn bench_utf8_idx_column(c: &mut Criterion) {
+    let n = 20_000;
+    let strings = StringArray::from(vec![Some("2024-10-18"); n]);
+    let pat = StringArray::from(vec![Some(r"(\d{4})-(\d{2})-(\d{2})")]);
+    let idx = Int64Array::from((0..n).map(|i| Some((i % 4) as i64)).collect::<Vec<_>>());
+    bench_utf8(
+        c,
+        "utf8 / scalar pattern / idx column 0..3",
+        &strings,
+        &pat,
+        &idx,
+        64,
+    );
+}
+
+fn bench_utf8_very_long_strings(c: &mut Criterion) {
+    let n = 1_000;
+    // ~16 KiB payloads with the match near the end
+    let blob = format!(
+        "{} trace_id=abc123def456 end",
+        "lorem ipsum dolor ".repeat(900)
+    );
+    let strings = StringArray::from(vec![Some(blob.as_str()); n]);
+    let pat = StringArray::from(vec![Some(r"trace_id=([0-9a-f]+)")]);
+    let idx = Int64Array::from(vec![Some(1)]);
+    bench_utf8(
+        c,
+        "utf8 / 16KiB strings (1k rows) / scalar pat+idx",
+        &strings,
+        &pat,
+        &idx,
+        64,
+    );
+}
+
+fn bench_utf8_unicode(c: &mut Criterion) {
+    let n = 20_000;
+    let strings = StringArray::from(
+        (0..n)
+            .map(|i| Some(format!("用户 Ünïcødé 🚀 订单号：{i} — статус: готово ✅")))
+            .collect::<Vec<_>>(),
+    );
+    let idx = Int64Array::from(vec![Some(1)]);
+    let han = StringArray::from(vec![Some(r"(\p{Han}+)：(\d+)")]);
+    bench_utf8(
+        c,
+        "utf8 / unicode text / \\p{Han} class",
+        &strings,
+        &han,
+        &idx,
+        64,
+    );
+    let word = StringArray::from(vec![Some(r"статус: (\w+)")]);
+    bench_utf8(
+        c,
+        "utf8 / unicode text / cyrillic \\w+",
+        &strings,
+        &word,
+        &idx,
+        64,
+    );
+}
+
+fn bench_session_context(c: &mut Criterion) {
+    let n = 20_000;
+    let schema = Arc::new(Schema::new(vec![Field::new("line", DataType::Utf8, true)]));
+    let lines = Arc::new(StringArray::from(apache_lines(n))) as ArrayRef;
+    let batch = RecordBatch::try_new(schema.clone(), vec![lines]).unwrap();
+    let table = Arc::new(MemTable::try_new(schema, vec![vec![batch]]).unwrap());
+    let ctx = SessionContext::new();
+    let udf = regexp_extract_udf();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    // planning, scalar-argument to_array and collect included in every iteration
+    c.bench_function("session / select regexp_extract / collect", |b| {
+        b.iter(|| {
+            let batches = rt.block_on(async {
+                ctx.read_table(table.clone())
+                    .unwrap()
+                    .select(vec![udf.call(vec![
+                        col("line"),
+                        lit(r#"" (\d{3}) \d+ ""#),
+                        lit(1_i64),
+                    ])])
+                    .unwrap()
+                    .collect()
+                    .await
+                    .unwrap()
+            });
+            black_box(batches);
+        });
+    });
+}
+
+#[cfg(feature = "fancy-regex")]
+fn bench_fancy_regex(c: &mut Criterion) {
+    let n = 20_000;
+    let strings = StringArray::from(apache_lines(n));
+    let idx = Int64Array::from(vec![Some(0)]);
+    // lookbehind and backreference only compile under fancy-regex
+    let lookbehind = StringArray::from(vec![Some(r"(?<=/items/)\d+")]);
+    bench_utf8(
+        c,
+        "fancy / apache log / lookbehind",
+        &strings,
+        &lookbehind,
+        &idx,
+        64,
+    );
+    let backref = StringArray::from(vec![Some(r"(\d)\1")]);
+    bench_utf8(
+        c,
+        "fancy / apache log / backreference",
+        &strings,
+        &backref,
+        &idx,
+        64,
+    );
+}
+
 criterion_group!(
     benches,
     bench_utf8_scalar_pattern,
     bench_utf8_column_pattern_repeated,
     bench_largeutf8_scalar_pattern,
+    bench_utf8_column_pattern_shuffled,
     bench_utf8_unique_patterns_miss,
     bench_utf8_long_strings,
     bench_utf8_no_match,
-    bench_utf8_heavy_alternation
+    bench_utf8_heavy_alternation,
+    bench_utf8_low_selectivity_literal,
+    bench_utf8_log_formats,
+    bench_utf8_high_cardinality_patterns,
+    bench_utf8_nullable,
+    bench_utf8_idx_column,
+    bench_utf8_very_long_strings,
+    bench_utf8_unicode,
+    bench_session_context
 );
+#[cfg(feature = "fancy-regex")]
+criterion_group!(fancy, bench_fancy_regex);
+
+#[cfg(feature = "fancy-regex")]
+criterion_main!(benches, fancy);
+#[cfg(not(feature = "fancy-regex"))]
 criterion_main!(benches);
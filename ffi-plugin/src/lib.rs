@@ -0,0 +1,11 @@
+//! The `cdylib` a DataFusion host loads `regexp_extract` from: exports
+//! `datafusion_regexp_extract_udf::ffi::RegexpExtractModule` as the `abi_stable` root
+//! module. Build it with `cargo build --release -p datafusion-regexp-extract-udf-plugin`.
+
+use abi_stable::export_root_module;
+use datafusion_regexp_extract_udf::ffi::{RegexpExtractModuleRef, module};
+
+#[export_root_module]
+pub fn get_module() -> RegexpExtractModuleRef {
+    module()
+}
@@ -0,0 +1,45 @@
+use abi_stable::library::RootModule;
+use datafusion::arrow::array::StringArray;
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::prelude::SessionContext;
+use datafusion_ffi::udf::{FFI_ScalarUDF, ForeignScalarUDF};
+
+use datafusion_regexp_extract_udf::ffi::RegexpExtractModuleRef;
+
+/// Loads the plugin from the cdylib cargo built for this package's tests.
+fn module() -> RegexpExtractModuleRef {
+    let exe = std::env::current_exe().unwrap();
+    RegexpExtractModuleRef::load_from_directory(exe.parent().unwrap()).unwrap()
+}
+
+async fn query(ffi_udf: &FFI_ScalarUDF, sql: &str) -> datafusion::common::Result<Option<String>> {
+    let ctx = SessionContext::new();
+    ctx.register_udf(ScalarUDF::new_from_impl(
+        ForeignScalarUDF::try_from(ffi_udf).unwrap(),
+    ));
+    let batches = ctx.sql(sql).await?.collect().await?;
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    Ok(out.iter().next().flatten().map(str::to_string))
+}
+
+#[tokio::test]
+async fn udf_loads_from_the_plugin() {
+    let module = module();
+    let udf = module.create_udf()();
+    let sql = r"SELECT regexp_extract('id=42', 'id=(\d+)', 1)";
+    assert_eq!(query(&udf, sql).await.unwrap().as_deref(), Some("42"));
+
+    let sql = "SELECT regexp_extract('id=42', '(', 1)";
+    assert!(query(&udf, sql).await.is_err());
+    let udf =
+        module.create_udf_with()("regexp_extract/1\ninvalid_pattern_mode=empty_string".into())
+            .unwrap();
+    assert_eq!(query(&udf, sql).await.unwrap().as_deref(), Some(""));
+
+    let err = module.create_udf_with()("regexp_extract/1\ncache_size=lots".into()).unwrap_err();
+    assert!(err.contains("cache_size"), "{err}");
+}
@@ -0,0 +1,70 @@
+//! Drives the public kernel entry points with arbitrary strings, patterns and indices.
+//!
+//! The three columns have independent lengths and NULLs, so besides malformed patterns
+//! this covers scalar (length-1) arguments and mismatched lengths, which must come back
+//! as `RegexpExtractError`s rather than panics.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use datafusion::arrow::array::{Array, Int32Array, Int64Array, LargeStringArray, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use libfuzzer_sys::fuzz_target;
+
+use datafusion_regexp_extract_udf::InvalidPatternMode;
+use datafusion_regexp_extract_udf::kernel::{run_large_utf8_largeutf8, run_utf8_utf8};
+
+#[derive(Debug, Arbitrary)]
+enum Mode {
+    Error,
+    EmptyString,
+    Collect,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    strings: Vec<Option<String>>,
+    patterns: Vec<Option<String>>,
+    idx: Vec<Option<i64>>,
+    idx_i32: bool,
+    large: bool,
+    cache_cap: u8,
+    mode: Mode,
+}
+
+fuzz_target!(|input: Input| {
+    let mode = match input.mode {
+        Mode::Error => InvalidPatternMode::Error,
+        Mode::EmptyString => InvalidPatternMode::EmptyString,
+        Mode::Collect => InvalidPatternMode::Collect,
+    };
+    let i64s = Int64Array::from(input.idx.clone());
+    let i32s = Int32Array::from(
+        input
+            .idx
+            .iter()
+            .map(|i| i.map(|i| i as i32))
+            .collect::<Vec<_>>(),
+    );
+    let (idx_i64, idx_i32) = if input.idx_i32 {
+        (None, Some(&i32s))
+    } else {
+        (Some(&i64s), None)
+    };
+    let cap = input.cache_cap as usize;
+
+    let out = if input.large {
+        let s = LargeStringArray::from(input.strings.clone());
+        let p = LargeStringArray::from(input.patterns);
+        let dt = DataType::LargeUtf8;
+        run_large_utf8_largeutf8(&s, &p, idx_i64, idx_i32, &dt, cap, mode)
+    } else {
+        let s = StringArray::from(input.strings.clone());
+        let p = StringArray::from(input.patterns);
+        run_utf8_utf8(&s, &p, idx_i64, idx_i32, &DataType::Utf8, cap, mode)
+    };
+
+    if let Ok(out) = out {
+        assert_eq!(out.len(), input.strings.len());
+    }
+});
@@ -0,0 +1,28 @@
+//! Compiles arbitrary pattern text through `PatternCache::get_or_compile`, then matches
+//! the compiled pattern against an arbitrary haystack.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use datafusion_regexp_extract_udf::pattern_cache::PatternCache;
+use datafusion_regexp_extract_udf::re;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    patterns: Vec<String>,
+    haystack: String,
+    cache_cap: u8,
+}
+
+fuzz_target!(|input: Input| {
+    let mut cache = PatternCache::new(input.cache_cap as usize);
+    for pat in &input.patterns {
+        if let Ok(regex) = cache.get_or_compile(pat) {
+            let _ = re::find(regex, &input.haystack);
+        }
+    }
+    let stats = cache.stats();
+    assert_eq!(stats.hits + stats.misses, input.patterns.len() as u64);
+});
@@ -0,0 +1,12 @@
+//! The Python extension module `datafusion_regexp_extract_udf`: the classes and functions
+//! `datafusion_regexp_extract_udf::python::register` adds. Build a wheel with
+//! `maturin build --release` from this directory (`pyproject.toml` enables
+//! `extension-module`).
+
+use pyo3::prelude::*;
+
+#[pymodule]
+#[pyo3(name = "datafusion_regexp_extract_udf")]
+fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    datafusion_regexp_extract_udf::python::register(m)
+}
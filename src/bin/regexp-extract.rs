@@ -0,0 +1,323 @@
+//! `regexp-extract`: runs `regexp_extract` over a local CSV, Parquet or NDJSON file.
+//!
+//! ```text
+//! regexp-extract access.csv -e user=line:'user=(\w+)':1 -e status=line:' (\d{3}) ':1
+//! regexp-extract events.ndjson -e id=msg:'id=(\d+)':1 --config cache_size=256 -o ids.csv
+//! ```
+//!
+//! Each `--extract name=col:pattern:idx` adds a column `name`; the pattern may itself contain
+//! `:` (the first `:` ends the column, the last one starts `idx`). `--config key=value` sets a
+//! `RegexpExtractConfig` field by name.
+//!
+//! Under `invalid_pattern_mode=collect` each failed row is printed to stderr as
+//! `name: batch B row R`; the input is read as one partition so batch `B` is the `B`-th
+//! batch of the file.
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::dataframe::DataFrameWriteOptions;
+use datafusion::prelude::{
+    CsvReadOptions, NdJsonReadOptions, ParquetReadOptions, SessionConfig, SessionContext, ident,
+    lit,
+};
+use datafusion_regexp_extract_udf::{
+    ErrorLog, InvalidPatternMode, RegexpExtractConfig, regexp_extract_udf_with,
+};
+use std::error::Error;
+use std::path::Path;
+use std::process::ExitCode;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Parquet,
+    Json,
+    Table,
+}
+
+impl Format {
+    fn parse(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "parquet" => Ok(Self::Parquet),
+            "json" | "ndjson" | "jsonl" => Ok(Self::Json),
+            "table" => Ok(Self::Table),
+            other => Err(format!("unknown format `{other}`").into()),
+        }
+    }
+
+    fn from_path(path: &str) -> Result<Self> {
+        let ext = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| format!("cannot infer the format of `{path}`; pass it explicitly"))?;
+        Self::parse(ext)
+    }
+}
+
+/// One `--extract name=col:pattern:idx`.
+#[derive(Debug, PartialEq, Eq)]
+struct Extract {
+    name: String,
+    column: String,
+    pattern: String,
+    idx: i64,
+}
+
+impl Extract {
+    fn parse(spec: &str) -> Result<Self> {
+        let bad = || format!("invalid --extract `{spec}`, expected name=col:pattern:idx");
+        let (name, rest) = spec.split_once('=').ok_or_else(bad)?;
+        let (column, rest) = rest.split_once(':').ok_or_else(bad)?;
+        let (pattern, idx) = rest.rsplit_once(':').ok_or_else(bad)?;
+        if name.is_empty() || column.is_empty() {
+            return Err(bad().into());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            column: column.to_string(),
+            pattern: pattern.to_string(),
+            idx: idx.parse().map_err(|_| bad())?,
+        })
+    }
+}
+
+fn cli() -> Command {
+    Command::new("regexp-extract")
+        .about("Run Spark-compatible regexp_extract over a CSV, Parquet or NDJSON file")
+        .arg(
+            Arg::new("input")
+                .required(true)
+                .help("Input file (.csv, .parquet, .json/.ndjson)"),
+        )
+        .arg(
+            Arg::new("extract")
+                .short('e')
+                .long("extract")
+                .action(ArgAction::Append)
+                .required(true)
+                .value_name("NAME=COL:PATTERN:IDX")
+                .help("Add column NAME = regexp_extract(COL, PATTERN, IDX); repeatable"),
+        )
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .action(ArgAction::Append)
+                .value_name("KEY=VALUE")
+                .help(
+                    "Set a RegexpExtractConfig field: cache_size, invalid_pattern_mode, \
+                     parallel_threshold, binary_output; repeatable",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("csv|parquet|json")
+                .help("Input format (default: from the file extension)"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("PATH")
+                .help("Write the result to PATH instead of printing a table"),
+        )
+        .arg(
+            Arg::new("output-format")
+                .long("output-format")
+                .value_name("table|csv|parquet|json")
+                .help("Output format (default: from the output extension, else table)"),
+        )
+        .arg(
+            Arg::new("only-extracted")
+                .long("only-extracted")
+                .action(ArgAction::SetTrue)
+                .help("Output only the extracted columns"),
+        )
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .value_parser(clap::value_parser!(usize))
+                .help("Keep at most this many rows"),
+        )
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(cli().get_matches()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("regexp-extract: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(m: ArgMatches) -> Result<()> {
+    let input = m.get_one::<String>("input").expect("required");
+    let extracts = m
+        .get_many::<String>("extract")
+        .expect("required")
+        .map(|s| Extract::parse(s))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut cfg = RegexpExtractConfig::new();
+    for kv in m.get_many::<String>("config").into_iter().flatten() {
+        let (key, value) = kv
+            .split_once('=')
+            .ok_or_else(|| format!("invalid --config `{kv}`, expected key=value"))?;
+        cfg.set(key, value)?;
+    }
+    let collect = cfg.invalid_pattern_mode == InvalidPatternMode::Collect;
+
+    // one partition keeps the batch ordinals of logged errors in file order
+    let ctx = SessionContext::new_with_config(SessionConfig::new().with_target_partitions(1));
+    ctx.register_udf(regexp_extract_udf_with(cfg.clone()));
+
+    let in_format = match m.get_one::<String>("format") {
+        Some(f) => Format::parse(f)?,
+        None => Format::from_path(input)?,
+    };
+    register_input(&ctx, input, in_format).await?;
+
+    let df = ctx.table("input").await?;
+    let mut exprs = if m.get_flag("only-extracted") {
+        Vec::new()
+    } else {
+        df.schema()
+            .columns()
+            .into_iter()
+            .map(|c| ident(c.name))
+            .collect()
+    };
+    // a log per column: every call then sees each input batch once, so its batch
+    // ordinals are the file's
+    let mut logs = Vec::new();
+    for e in &extracts {
+        let udf = if collect {
+            let log = ErrorLog::new();
+            logs.push((e.name.as_str(), log.clone()));
+            regexp_extract_udf_with(cfg.clone().error_log(log))
+        } else {
+            regexp_extract_udf_with(cfg.clone())
+        };
+        exprs.push(
+            udf.call(vec![ident(&e.column), lit(e.pattern.as_str()), lit(e.idx)])
+                .alias(&e.name),
+        );
+    }
+    let mut df = df.select(exprs)?;
+    if let Some(n) = m.get_one::<usize>("limit") {
+        df = df.limit(0, Some(*n))?;
+    }
+
+    let output = m.get_one::<String>("output");
+    let out_format = match (m.get_one::<String>("output-format"), output) {
+        (Some(f), _) => Format::parse(f)?,
+        (None, Some(path)) => Format::from_path(path)?,
+        (None, None) => Format::Table,
+    };
+    match (out_format, output) {
+        (Format::Table, None) => {
+            println!("{}", pretty_format_batches(&df.collect().await?)?);
+        }
+        (Format::Table, Some(path)) => {
+            std::fs::write(
+                path,
+                pretty_format_batches(&df.collect().await?)?.to_string(),
+            )?;
+        }
+        (format, None) => {
+            return Err(format!("{format:?} output needs --output PATH").into());
+        }
+        (Format::Csv, Some(path)) => {
+            df.write_csv(path, single_file(), None).await?;
+        }
+        (Format::Parquet, Some(path)) => {
+            df.write_parquet(path, single_file(), None).await?;
+        }
+        (Format::Json, Some(path)) => {
+            df.write_json(path, single_file(), None).await?;
+        }
+    }
+
+    for (name, log) in logs {
+        for e in log.take() {
+            eprintln!(
+                "{name}: batch {} row {}: {:?} `{}`: {}",
+                e.batch.unwrap_or_default(),
+                e.row,
+                e.kind,
+                e.pattern,
+                e.message
+            );
+        }
+    }
+    Ok(())
+}
+
+fn single_file() -> DataFrameWriteOptions {
+    DataFrameWriteOptions::new().with_single_file_output(true)
+}
+
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map_or_else(String::new, |e| format!(".{e}"))
+}
+
+async fn register_input(ctx: &SessionContext, path: &str, format: Format) -> Result<()> {
+    let ext = extension(path);
+    match format {
+        Format::Csv => {
+            ctx.register_csv("input", path, CsvReadOptions::new().file_extension(&ext))
+                .await?
+        }
+        Format::Json => {
+            ctx.register_json(
+                "input",
+                path,
+                NdJsonReadOptions::default().file_extension(&ext),
+            )
+            .await?
+        }
+        Format::Parquet => {
+            ctx.register_parquet(
+                "input",
+                path,
+                ParquetReadOptions::default().file_extension(&ext),
+            )
+            .await?
+        }
+        Format::Table => return Err("`table` is an output format only".into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_spec_keeps_colons_in_pattern() {
+        let e = Extract::parse(r"t=line:(\d{2}):(\d{2}):2").unwrap();
+        assert_eq!(e.name, "t");
+        assert_eq!(e.column, "line");
+        assert_eq!(e.pattern, r"(\d{2}):(\d{2})");
+        assert_eq!(e.idx, 2);
+        assert!(Extract::parse("line:(a):1").is_err());
+        assert!(Extract::parse("t=line:(a):x").is_err());
+    }
+
+    #[test]
+    fn formats_from_extensions() {
+        assert_eq!(Format::from_path("a/b.CSV").unwrap(), Format::Csv);
+        assert_eq!(Format::from_path("x.ndjson").unwrap(), Format::Json);
+        assert!(Format::from_path("noext").is_err());
+        cli().debug_assert();
+    }
+}
@@ -0,0 +1,68 @@
+//! `regexp-lint [FILE]`: lints one pattern per line from `FILE` (stdin when omitted or `-`)
+//! and prints a JSON array with one report per pattern (see `lint::Report::to_json`).
+//!
+//! Blank lines are skipped. Exits with 1 when a pattern does not compile with the engine
+//! `regexp_extract` uses, 2 on usage or I/O errors.
+
+use datafusion_regexp_extract_udf::lint::lint_pattern;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let input: Box<dyn BufRead> = match args.as_slice() {
+        [] => Box::new(io::stdin().lock()),
+        [p] if p == "-" => Box::new(io::stdin().lock()),
+        [p] if p == "-h" || p == "--help" => {
+            println!("usage: regexp-lint [FILE]");
+            return ExitCode::SUCCESS;
+        }
+        [path] => match File::open(path) {
+            Ok(f) => Box::new(BufReader::new(f)),
+            Err(e) => {
+                eprintln!("regexp-lint: {path}: {e}");
+                return ExitCode::from(2);
+            }
+        },
+        _ => {
+            eprintln!("usage: regexp-lint [FILE]");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(input, &mut io::stdout().lock()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("regexp-lint: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Writes the reports; `Ok(false)` when some pattern does not compile.
+fn run(input: Box<dyn BufRead>, out: &mut impl Write) -> io::Result<bool> {
+    let mut all_compile = true;
+    let mut first = true;
+    writeln!(out, "[")?;
+    for line in input.lines() {
+        let line = line?;
+        let pat = line.strip_suffix('\r').unwrap_or(&line);
+        if pat.trim().is_empty() {
+            continue;
+        }
+        let report = lint_pattern(pat);
+        all_compile &= report.compiles();
+        if !first {
+            writeln!(out, ",")?;
+        }
+        first = false;
+        write!(out, "  {}", report.to_json())?;
+    }
+    if !first {
+        writeln!(out)?;
+    }
+    writeln!(out, "]")?;
+    Ok(all_compile)
+}
@@ -0,0 +1,171 @@
+//! `Binary` / `LargeBinary` / `BinaryView` input for `regexp_extract`.
+//!
+//! Values are matched as raw bytes with `regex::bytes` (see [`BytesRegex`]); pattern and
+//! `idx` follow the same rules as for string input. The result is binary of the input's
+//! width, or lossily decoded text with [`BinaryOutput::LossyUtf8`]. The string-only
+//! optimizations (literal prefilter, pattern grouping, `parallel`) are not applied here.
+
+use crate::config::{BinaryOutput, RegexpExtractConfig};
+use crate::error::RegexpExtractError;
+use crate::kernel::{ScalarPattern, StrArray, check_lengths, row_failure};
+use crate::pattern_cache::PatternCache;
+use crate::re::{BytesRegex, extract_bytes};
+use datafusion::arrow::array::{
+    Array, ArrayAccessor, ArrayRef, AsArray, BinaryArray, BinaryViewArray, Int32Array, Int64Array,
+    LargeBinaryArray, LargeStringArray, StringArray, StringViewArray,
+};
+use datafusion::arrow::datatypes::DataType;
+use regex::bytes::CaptureLocations;
+use std::sync::Arc;
+
+/// Whether `dt` is a binary type accepted as `regexp_extract` input.
+pub fn is_binary(dt: &DataType) -> bool {
+    matches!(
+        dt,
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView
+    )
+}
+
+/// Result type of `regexp_extract` over binary input `dt`.
+pub fn binary_return_type(dt: &DataType, out: BinaryOutput) -> Option<DataType> {
+    match (dt, out) {
+        (DataType::Binary | DataType::LargeBinary | DataType::BinaryView, BinaryOutput::Binary) => {
+            Some(dt.clone())
+        }
+        (DataType::Binary, BinaryOutput::LossyUtf8) => Some(DataType::Utf8),
+        (DataType::LargeBinary, BinaryOutput::LossyUtf8) => Some(DataType::LargeUtf8),
+        (DataType::BinaryView, BinaryOutput::LossyUtf8) => Some(DataType::Utf8View),
+        _ => None,
+    }
+}
+
+/// Binary counterpart of `kernel::run_generic`; `values` must satisfy [`is_binary`].
+pub(crate) fn run_binary<P: StrArray>(
+    values: &dyn Array,
+    patterns: &P,
+    idx_i64: Option<&Int64Array>,
+    idx_i32: Option<&Int32Array>,
+    cfg: &RegexpExtractConfig,
+) -> Result<ArrayRef, RegexpExtractError> {
+    let out = match values.data_type() {
+        DataType::Binary => {
+            extract_all(values.as_binary::<i32>(), patterns, idx_i64, idx_i32, cfg)?
+        }
+        DataType::LargeBinary => {
+            extract_all(values.as_binary::<i64>(), patterns, idx_i64, idx_i32, cfg)?
+        }
+        DataType::BinaryView => {
+            extract_all(values.as_binary_view(), patterns, idx_i64, idx_i32, cfg)?
+        }
+        other => return Err(RegexpExtractError::UnexpectedInputType(other.clone())),
+    };
+
+    let lossy = || {
+        out.iter()
+            .map(|v| v.map(String::from_utf8_lossy))
+            .collect::<Vec<_>>()
+    };
+    let arr: ArrayRef = match (values.data_type(), cfg.binary_output) {
+        (DataType::Binary, BinaryOutput::Binary) => Arc::new(BinaryArray::from_iter(out)),
+        (DataType::LargeBinary, BinaryOutput::Binary) => Arc::new(LargeBinaryArray::from_iter(out)),
+        (_, BinaryOutput::Binary) => Arc::new(BinaryViewArray::from_iter(out)),
+        (DataType::Binary, BinaryOutput::LossyUtf8) => Arc::new(StringArray::from_iter(lossy())),
+        (DataType::LargeBinary, BinaryOutput::LossyUtf8) => {
+            Arc::new(LargeStringArray::from_iter(lossy()))
+        }
+        (_, BinaryOutput::LossyUtf8) => Arc::new(StringViewArray::from_iter(lossy())),
+    };
+    Ok(arr)
+}
+
+/// Per-row extracted bytes: `None` for NULL, empty when nothing matched.
+fn extract_all<'a, A, P>(
+    values: A,
+    patterns: &P,
+    idx_i64: Option<&Int64Array>,
+    idx_i32: Option<&Int32Array>,
+    cfg: &RegexpExtractConfig,
+) -> Result<Vec<Option<&'a [u8]>>, RegexpExtractError>
+where
+    A: ArrayAccessor<Item = &'a [u8]>,
+    P: StrArray,
+{
+    let n = values.len();
+    let idx_len = idx_i64.map(Array::len).or(idx_i32.map(Array::len));
+    let idx_len = idx_len.ok_or(RegexpExtractError::MissingIdxArray)?;
+    check_lengths(n, &[("pattern", patterns.len()), ("idx", idx_len)])?;
+    let mut cache = PatternCache::<BytesRegex>::with_capacity(cfg.cache_size)
+        .with_shared(cfg.pattern_cache.clone());
+    let mut out = Vec::with_capacity(n);
+
+    let pat_scalar = patterns.len() == 1;
+    let scalar = if pat_scalar && !patterns.is_null(0) {
+        Some(ScalarPattern::<BytesRegex>::compile(
+            patterns.value(0),
+            cfg,
+        )?)
+    } else {
+        None
+    };
+    let mut locs = scalar
+        .as_ref()
+        .and_then(ScalarPattern::regex)
+        .map(BytesRegex::capture_locations);
+    let mut col_locs: Option<(&str, CaptureLocations)> = None;
+
+    for i in 0..n {
+        let p = if pat_scalar { 0 } else { i };
+        if values.is_null(i) || patterns.is_null(p) {
+            out.push(None);
+            continue;
+        }
+        let idx = match (idx_i64, idx_i32) {
+            (Some(a), _) => {
+                let j = if a.len() == 1 { 0 } else { i };
+                (!a.is_null(j)).then(|| a.value(j))
+            }
+            (_, Some(a)) => {
+                let j = if a.len() == 1 { 0 } else { i };
+                (!a.is_null(j)).then(|| a.value(j) as i64)
+            }
+            _ => return Err(RegexpExtractError::MissingIdxArray),
+        };
+        let Some(idx) = idx else {
+            out.push(None);
+            continue;
+        };
+        let s = values.value(i);
+        let pat = patterns.value(p);
+        if idx < 0 {
+            let err = RegexpExtractError::NegativeIndex(idx);
+            out.push(row_failure(cfg, i, pat, err).resolve(Some(&s[..0]), None)?);
+            continue;
+        }
+
+        if let Some(scalar) = &scalar {
+            let (Some(re), Some(locs)) = (scalar.regex(), &mut locs) else {
+                out.push(scalar.failure(i, cfg).resolve(Some(&s[..0]), None)?);
+                continue;
+            };
+            let span = extract_bytes(re, locs, s, idx as usize);
+            out.push(Some(span.map_or(&s[..0], |(a, b)| &s[a..b])));
+        } else {
+            let re = match cache.get_or_compile(pat) {
+                Ok(r) => r,
+                Err(e) => {
+                    out.push(row_failure(cfg, i, pat, e).resolve(Some(&s[..0]), None)?);
+                    continue;
+                }
+            };
+            // reuse the slot buffer while consecutive rows share a pattern
+            let row_locs = match &mut col_locs {
+                Some((last, locs)) if *last == pat => locs,
+                _ => &mut col_locs.insert((pat, re.capture_locations())).1,
+            };
+            let span = extract_bytes(re, row_locs, s, idx as usize);
+            out.push(Some(span.map_or(&s[..0], |(a, b)| &s[a..b])));
+        }
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,371 @@
+//! Multi-pattern classification UDFs built on a single `PatternSet`.
+//!
+//! `regexp_classify(str)` returns the label of the first pattern that matches and
+//! `regexp_match_any(str)` the indices of every matching pattern. The pattern list is
+//! fixed when the UDF is built and compiled once per UDF instance, so hundreds of
+//! patterns cost one pass per row instead of one `regexp_extract` call each.
+
+use crate::config::{InvalidPatternMode, RegexpExtractConfig};
+use crate::error::RegexpExtractError;
+use crate::kernel::{RowFailure, StrArray, StrBuilder, in_batch, row_failure};
+use crate::re::{PatternSet, pattern_error};
+use crate::session::Reconfigure;
+use crate::types::to_array;
+use datafusion::arrow::array::{Array, ArrayRef, Int32Builder, ListBuilder};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::{as_large_string_array, as_string_array};
+use datafusion::common::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+use std::any::Any;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+/// `regexp_classify(str)`: label of the first matching pattern, NULL when none matches.
+///
+/// Without `labels`, a pattern's label is the pattern text itself. Invalid patterns fail
+/// construction in `InvalidPatternMode::Error`; in `EmptyString` and `Collect` mode they
+/// never match. `Collect` records each one in the error log when the UDF is built, with
+/// `row` set to its position in `patterns`.
+pub fn regexp_classify_udf(
+    patterns: &[&str],
+    labels: Option<&[&str]>,
+    cfg: RegexpExtractConfig,
+) -> Result<ScalarUDF, RegexpExtractError> {
+    let rules = Rules::new(patterns, labels, &cfg)?;
+    Ok(ScalarUDF::from(RegexpClassifyUdf::new(
+        Arc::new(rules),
+        Output::FirstLabel,
+        cfg,
+    )))
+}
+
+/// `regexp_match_any(str)`: 0-based indices (in pattern order) of all matching patterns,
+/// as `List<Int32>`; empty when none matches.
+pub fn regexp_match_any_udf(
+    patterns: &[&str],
+    cfg: RegexpExtractConfig,
+) -> Result<ScalarUDF, RegexpExtractError> {
+    let rules = Rules::new(patterns, None, &cfg)?;
+    Ok(ScalarUDF::from(RegexpClassifyUdf::new(
+        Arc::new(rules),
+        Output::MatchingIndices,
+        cfg,
+    )))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Output {
+    FirstLabel,
+    MatchingIndices,
+}
+
+/// Patterns, their labels and the compiled set of the valid ones.
+#[derive(Debug)]
+struct Rules {
+    patterns: Vec<String>,
+    labels: Vec<String>,
+    set: PatternSet,
+    /// Set member -> position in `patterns` (differs once invalid patterns are dropped).
+    members: Vec<usize>,
+    /// The valid patterns as one alternation, reported for rows whose match fails.
+    set_pattern: String,
+}
+
+impl Rules {
+    fn new(
+        patterns: &[&str],
+        labels: Option<&[&str]>,
+        cfg: &RegexpExtractConfig,
+    ) -> Result<Self, RegexpExtractError> {
+        let labels = match labels {
+            Some(l) if l.len() != patterns.len() => {
+                return Err(RegexpExtractError::LabelCountMismatch {
+                    patterns: patterns.len(),
+                    labels: l.len(),
+                });
+            }
+            Some(l) => l.iter().map(|s| s.to_string()).collect(),
+            None => patterns.iter().map(|s| s.to_string()).collect(),
+        };
+
+        let (set, errors) =
+            PatternSet::new_lenient(patterns).map_err(|e| pattern_error(&patterns.join("|"), e))?;
+        let mut members = Vec::with_capacity(patterns.len());
+        for (i, (pat, err)) in patterns.iter().zip(errors).enumerate() {
+            let Some(err) = err else {
+                members.push(i);
+                continue;
+            };
+            let err = pattern_error(pat, err);
+            match cfg.invalid_pattern_mode {
+                InvalidPatternMode::Error => return Err(err),
+                // logged under `Collect`, dropped under `EmptyString`
+                _ => {
+                    let _ = row_failure(cfg, i, pat, err);
+                }
+            }
+        }
+        let set_pattern = members
+            .iter()
+            .map(|&i| patterns[i])
+            .collect::<Vec<_>>()
+            .join("|");
+
+        Ok(Self {
+            patterns: patterns.iter().map(|s| s.to_string()).collect(),
+            labels,
+            set,
+            members,
+            set_pattern,
+        })
+    }
+
+    /// Row `row` whose match failed at runtime (e.g. `fancy-regex` backtrack limit):
+    /// no match under `EmptyString`, NULL and logged under `Collect`.
+    fn on_match_error(
+        &self,
+        cfg: &RegexpExtractConfig,
+        row: usize,
+        e: impl ToString,
+    ) -> Result<RowFailure, RegexpExtractError> {
+        let err = RegexpExtractError::MatchError(e.to_string());
+        match row_failure(cfg, row, &self.set_pattern, err) {
+            RowFailure::Fail(e) => Err(e),
+            failure => Ok(failure),
+        }
+    }
+
+    fn first_labels<S: StrArray>(
+        &self,
+        strings: &S,
+        cfg: &RegexpExtractConfig,
+    ) -> Result<ArrayRef, RegexpExtractError> {
+        let mut b = S::builder_with_capacity(strings.len(), strings.len() * 8);
+        for i in 0..strings.len() {
+            if strings.is_null(i) {
+                b.append_null();
+                continue;
+            }
+            match self.set.first_match(strings.value(i)) {
+                Ok(Some(m)) => b.append_value(&self.labels[self.members[m]]),
+                Ok(None) => b.append_null(),
+                Err(e) => {
+                    self.on_match_error(cfg, i, e)?;
+                    b.append_null();
+                }
+            }
+        }
+        Ok(b.finish_array())
+    }
+
+    fn matching_indices<S: StrArray>(
+        &self,
+        strings: &S,
+        cfg: &RegexpExtractConfig,
+    ) -> Result<ArrayRef, RegexpExtractError> {
+        let mut b = ListBuilder::with_capacity(Int32Builder::new(), strings.len())
+            .with_field(list_item_field());
+        let mut hits = Vec::new();
+        for i in 0..strings.len() {
+            if strings.is_null(i) {
+                b.append_null();
+                continue;
+            }
+            hits.clear();
+            if let Err(e) = self.set.matches_into(strings.value(i), &mut hits) {
+                if let RowFailure::Null(_) = self.on_match_error(cfg, i, e)? {
+                    b.append_null();
+                    continue;
+                }
+                hits.clear();
+            }
+            for &m in &hits {
+                b.values().append_value(self.members[m] as i32);
+            }
+            b.append(true);
+        }
+        Ok(Arc::new(b.finish()))
+    }
+}
+
+fn list_item_field() -> Arc<Field> {
+    Arc::new(Field::new_list_field(DataType::Int32, false))
+}
+
+/// Internal implementation of `regexp_classify` / `regexp_match_any`
+#[derive(Debug)]
+pub(crate) struct RegexpClassifyUdf {
+    signature: Signature,
+    rules: Arc<Rules>,
+    output: Output,
+    /// Applies to rows; invalid rules were already handled with the config at build time.
+    cfg: Arc<RegexpExtractConfig>,
+}
+
+impl RegexpClassifyUdf {
+    fn new(rules: Arc<Rules>, output: Output, cfg: RegexpExtractConfig) -> Self {
+        let sig = Signature::one_of(
+            vec![
+                TypeSignature::Exact(vec![DataType::Utf8]),
+                TypeSignature::Exact(vec![DataType::LargeUtf8]),
+            ],
+            Volatility::Immutable,
+        );
+        Self {
+            signature: sig,
+            rules,
+            output,
+            cfg: Arc::new(cfg),
+        }
+    }
+}
+
+impl Reconfigure for RegexpClassifyUdf {
+    fn config(&self) -> &RegexpExtractConfig {
+        &self.cfg
+    }
+
+    /// Keeps the compiled rules.
+    fn with_config(&self, cfg: RegexpExtractConfig) -> ScalarUDF {
+        ScalarUDF::from(Self::new(self.rules.clone(), self.output, cfg))
+    }
+}
+
+impl ScalarUDFImpl for RegexpClassifyUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        match self.output {
+            Output::FirstLabel => "regexp_classify",
+            Output::MatchingIndices => "regexp_match_any",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match (self.output, arg_types.first()) {
+            (Output::MatchingIndices, _) => Ok(DataType::List(list_item_field())),
+            (Output::FirstLabel, Some(DataType::Utf8)) => Ok(DataType::Utf8),
+            (Output::FirstLabel, Some(DataType::LargeUtf8)) => Ok(DataType::LargeUtf8),
+            (_, other) => Err(DataFusionError::Plan(format!(
+                "{} expects argument Utf8 or LargeUtf8, got: {other:?}",
+                self.name()
+            ))),
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        if args.args.len() != 1 {
+            return Err(DataFusionError::Execution(format!(
+                "{} expects 1 argument, got {}",
+                self.name(),
+                args.args.len()
+            )));
+        }
+        let s_arr = to_array(&args.args[0], args.number_rows)?;
+
+        if !matches!(s_arr.data_type(), DataType::Utf8 | DataType::LargeUtf8) {
+            return Err(DataFusionError::Execution(format!(
+                "{} expects argument Utf8 or LargeUtf8, got {:?}",
+                self.name(),
+                s_arr.data_type()
+            )));
+        }
+
+        let out = in_batch(&self.cfg, |cfg| -> Result<ArrayRef> {
+            Ok(match (s_arr.data_type(), self.output) {
+                (DataType::Utf8, Output::FirstLabel) => {
+                    self.rules.first_labels(as_string_array(&s_arr)?, cfg)?
+                }
+                (DataType::Utf8, Output::MatchingIndices) => {
+                    self.rules.matching_indices(as_string_array(&s_arr)?, cfg)?
+                }
+                (_, Output::FirstLabel) => self
+                    .rules
+                    .first_labels(as_large_string_array(&s_arr)?, cfg)?,
+                (_, Output::MatchingIndices) => self
+                    .rules
+                    .matching_indices(as_large_string_array(&s_arr)?, cfg)?,
+            })
+        })?;
+
+        Ok(ColumnarValue::Array(out))
+    }
+
+    // Instances differ by their pattern list, so name + signature is not enough.
+    fn equals(&self, other: &dyn ScalarUDFImpl) -> bool {
+        other.as_any().downcast_ref::<Self>().is_some_and(|o| {
+            self.output == o.output
+                && self.rules.patterns == o.rules.patterns
+                && self.rules.labels == o.rules.labels
+                && self.cfg.invalid_pattern_mode == o.cfg.invalid_pattern_mode
+        })
+    }
+
+    fn hash_value(&self) -> u64 {
+        let hasher = &mut DefaultHasher::new();
+        self.output.hash(hasher);
+        self.rules.patterns.hash(hasher);
+        self.rules.labels.hash(hasher);
+        self.cfg.invalid_pattern_mode.hash(hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ErrorLog, RowErrorKind};
+
+    #[test]
+    fn label_count_must_match() {
+        let err =
+            regexp_classify_udf(&["a", "b"], Some(&["x"]), RegexpExtractConfig::new()).unwrap_err();
+        assert!(matches!(
+            err,
+            RegexpExtractError::LabelCountMismatch {
+                patterns: 2,
+                labels: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn invalid_patterns_are_dropped_in_empty_string_mode() {
+        let cfg = RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::EmptyString);
+        let rules = Rules::new(&["(", "b", ")"], None, &cfg).unwrap();
+        assert_eq!(rules.members, vec![1]);
+
+        let strict = Rules::new(&["(", "b"], None, &RegexpExtractConfig::new());
+        assert!(matches!(
+            strict,
+            Err(RegexpExtractError::InvalidPattern { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_patterns_are_logged_in_collect_mode() {
+        let log = ErrorLog::new();
+        let cfg = RegexpExtractConfig::new()
+            .invalid_pattern_mode(InvalidPatternMode::Collect)
+            .error_log(log.clone());
+        let rules = Rules::new(&["a", "(", "b"], None, &cfg).unwrap();
+        assert_eq!(rules.members, vec![0, 2]);
+        assert_eq!(rules.set_pattern, "a|b");
+
+        let errors = log.take();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            (errors[0].row, errors[0].kind, errors[0].pattern.as_str()),
+            (1, RowErrorKind::InvalidPattern, "(")
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Serialized form of a `regexp_extract` UDF for `datafusion-proto` extension codecs.
+//!
+//! By default `datafusion-proto` writes only the function name, and the receiving side
+//! rebuilds `regexp_extract` from its registry with whatever config was registered there.
+//! `try_encode_udf`/`try_decode_udf` have the signatures of the `LogicalExtensionCodec` and
+//! `PhysicalExtensionCodec` methods, so a codec forwards to them to ship the config with the
+//! plan:
+//!
+//! ```text
+//! impl LogicalExtensionCodec for MyCodec {
+//!     fn try_encode_udf(&self, node: &ScalarUDF, buf: &mut Vec<u8>) -> Result<()> {
+//!         codec::try_encode_udf(node, buf)
+//!     }
+//!     fn try_decode_udf(&self, name: &str, buf: &[u8]) -> Result<Arc<ScalarUDF>> {
+//!         codec::try_decode_udf(name, buf)
+//!     }
+//!     // ...
+//! }
+//! ```
+//!
+//! With the `proto` feature, `RegexpExtractCodec` is such a codec, for plans that need no
+//! other extensions.
+//!
+//! The payload is UTF-8: a `regexp_extract/1` header line, then one `key=value` line per
+//! `RegexpExtractConfig::entries`, then one `warm=pattern` line per pattern of the
+//! `SharedPatternCache` (`\`, newline and carriage return escaped as `\\`, `\n`, `\r`), which
+//! the receiver warms into a shared cache of its own. The `ErrorLog` of a `Collect` config
+//! is not sent.
+
+use crate::config::RegexpExtractConfig;
+use crate::error::RegexpExtractError;
+use crate::pattern_cache::SharedPatternCache;
+use crate::udf::{regexp_extract_config, regexp_extract_udf_with};
+use datafusion::common::{Result, not_impl_err};
+use datafusion::logical_expr::ScalarUDF;
+use std::sync::Arc;
+
+const HEADER: &str = "regexp_extract/1";
+
+pub fn encode_config(cfg: &RegexpExtractConfig, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(HEADER.as_bytes());
+    for (key, value) in cfg.entries() {
+        buf.push(b'\n');
+        buf.extend_from_slice(key.as_bytes());
+        buf.push(b'=');
+        buf.extend_from_slice(value.as_bytes());
+    }
+    for pat in cfg
+        .pattern_cache
+        .iter()
+        .flat_map(SharedPatternCache::patterns)
+    {
+        buf.extend_from_slice(b"\nwarm=");
+        buf.extend_from_slice(escape(&pat).as_bytes());
+    }
+}
+
+/// Keeps a pattern on one payload line.
+fn escape(pat: &str) -> String {
+    let mut out = String::with_capacity(pat.len());
+    for c in pat.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(pat: &str) -> Option<String> {
+    let mut out = String::with_capacity(pat.len());
+    let mut chars = pat.chars();
+    while let Some(c) = chars.next() {
+        out.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(out)
+}
+
+/// Fields missing from the payload keep their defaults. `warm` lines are compiled into a
+/// new `SharedPatternCache`; one that does not compile here is left to the per-batch cache.
+pub fn decode_config(buf: &[u8]) -> Result<RegexpExtractConfig, RegexpExtractError> {
+    let bad = |message: String| RegexpExtractError::InvalidConfig {
+        key: "codec".to_string(),
+        message,
+    };
+    let text = std::str::from_utf8(buf).map_err(|e| bad(e.to_string()))?;
+    let mut lines = text.lines();
+    match lines.next() {
+        Some(HEADER) => {}
+        other => {
+            return Err(bad(format!(
+                "expected a `{HEADER}` payload, got `{}`",
+                other.unwrap_or_default()
+            )));
+        }
+    }
+    let mut cfg = RegexpExtractConfig::default();
+    let mut warm = Vec::new();
+    for line in lines {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| bad(format!("expected key=value, got `{line}`")))?;
+        if key == "warm" {
+            warm.push(unescape(value).ok_or_else(|| bad(format!("bad escape in `{line}`")))?);
+        } else {
+            cfg.set(key, value)?;
+        }
+    }
+    if !warm.is_empty() {
+        let cache = SharedPatternCache::new();
+        cache.warm(&warm);
+        cfg = cfg.pattern_cache(cache);
+    }
+    Ok(cfg)
+}
+
+/// Writes the config of a `regexp_extract` UDF; leaves `buf` empty for any other function,
+/// which `datafusion-proto` then resolves by name.
+pub fn try_encode_udf(node: &ScalarUDF, buf: &mut Vec<u8>) -> Result<()> {
+    if let Some(cfg) = regexp_extract_config(node) {
+        encode_config(cfg, buf);
+    }
+    Ok(())
+}
+
+pub fn try_decode_udf(name: &str, buf: &[u8]) -> Result<Arc<ScalarUDF>> {
+    if name != "regexp_extract" {
+        return not_impl_err!("no codec for scalar function {name}");
+    }
+    let cfg = decode_config(buf)?;
+    Ok(Arc::new(regexp_extract_udf_with(cfg)))
+}
+
+#[cfg(feature = "proto")]
+pub use proto::RegexpExtractCodec;
+
+#[cfg(feature = "proto")]
+mod proto {
+    use super::{try_decode_udf, try_encode_udf};
+    use datafusion::arrow::datatypes::SchemaRef;
+    use datafusion::catalog::TableProvider;
+    use datafusion::common::{Result, TableReference, not_impl_err};
+    use datafusion::execution::FunctionRegistry;
+    use datafusion::logical_expr::{Extension, LogicalPlan, ScalarUDF};
+    use datafusion::physical_plan::ExecutionPlan;
+    use datafusion::prelude::SessionContext;
+    use datafusion_proto::logical_plan::LogicalExtensionCodec;
+    use datafusion_proto::physical_plan::PhysicalExtensionCodec;
+    use std::sync::Arc;
+
+    /// Logical and physical extension codec that ships the config of `regexp_extract` UDFs.
+    /// Like `datafusion-proto`'s default codecs it has no extension nodes or custom tables;
+    /// a codec that needs those forwards its UDF methods to `try_encode_udf`/`try_decode_udf`.
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct RegexpExtractCodec;
+
+    impl LogicalExtensionCodec for RegexpExtractCodec {
+        fn try_decode(
+            &self,
+            _buf: &[u8],
+            _inputs: &[LogicalPlan],
+            _ctx: &SessionContext,
+        ) -> Result<Extension> {
+            not_impl_err!("RegexpExtractCodec has no extension nodes")
+        }
+
+        fn try_encode(&self, _node: &Extension, _buf: &mut Vec<u8>) -> Result<()> {
+            not_impl_err!("RegexpExtractCodec has no extension nodes")
+        }
+
+        fn try_decode_table_provider(
+            &self,
+            _buf: &[u8],
+            _table_ref: &TableReference,
+            _schema: SchemaRef,
+            _ctx: &SessionContext,
+        ) -> Result<Arc<dyn TableProvider>> {
+            not_impl_err!("RegexpExtractCodec has no table providers")
+        }
+
+        fn try_encode_table_provider(
+            &self,
+            _table_ref: &TableReference,
+            _node: Arc<dyn TableProvider>,
+            _buf: &mut Vec<u8>,
+        ) -> Result<()> {
+            not_impl_err!("RegexpExtractCodec has no table providers")
+        }
+
+        fn try_decode_udf(&self, name: &str, buf: &[u8]) -> Result<Arc<ScalarUDF>> {
+            try_decode_udf(name, buf)
+        }
+
+        fn try_encode_udf(&self, node: &ScalarUDF, buf: &mut Vec<u8>) -> Result<()> {
+            try_encode_udf(node, buf)
+        }
+    }
+
+    impl PhysicalExtensionCodec for RegexpExtractCodec {
+        fn try_decode(
+            &self,
+            _buf: &[u8],
+            _inputs: &[Arc<dyn ExecutionPlan>],
+            _registry: &dyn FunctionRegistry,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            not_impl_err!("RegexpExtractCodec has no extension nodes")
+        }
+
+        fn try_encode(&self, _node: Arc<dyn ExecutionPlan>, _buf: &mut Vec<u8>) -> Result<()> {
+            not_impl_err!("RegexpExtractCodec has no extension nodes")
+        }
+
+        fn try_decode_udf(&self, name: &str, buf: &[u8]) -> Result<Arc<ScalarUDF>> {
+            try_decode_udf(name, buf)
+        }
+
+        fn try_encode_udf(&self, node: &ScalarUDF, buf: &mut Vec<u8>) -> Result<()> {
+            try_encode_udf(node, buf)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BinaryOutput, InvalidPatternMode};
+
+    #[test]
+    fn udf_round_trips_with_its_config() {
+        let cfg = RegexpExtractConfig::new()
+            .cache_size(7)
+            .invalid_pattern_mode(InvalidPatternMode::Collect)
+            .parallel_threshold(10)
+            .span_byte_offsets(true)
+            .binary_output(BinaryOutput::LossyUtf8);
+        let mut buf = Vec::new();
+        try_encode_udf(&regexp_extract_udf_with(cfg.clone()), &mut buf).unwrap();
+
+        let udf = try_decode_udf("regexp_extract", &buf).unwrap();
+        let back = regexp_extract_config(&udf).unwrap();
+        assert_eq!(back.entries(), cfg.entries());
+        assert!(back.error_log.is_none());
+    }
+
+    #[test]
+    fn warmed_patterns_are_warmed_again_on_decode() {
+        let cache = SharedPatternCache::new();
+        let verbose = "(?x)\n  (\\d+)  # id\r\n";
+        cache.warm(&[r"(\d+)-(\d+)", verbose, "(unclosed"]);
+        let mut buf = Vec::new();
+        encode_config(&RegexpExtractConfig::new().pattern_cache(cache), &mut buf);
+
+        let back = decode_config(&buf).unwrap();
+        let warmed = back.pattern_cache.as_ref().unwrap();
+        assert_eq!(warmed.patterns(), [verbose, r"(\d+)-(\d+)"]);
+        assert!(decode_config(b"regexp_extract/1\nwarm=a\\qb").is_err());
+        assert!(
+            decode_config(b"regexp_extract/1")
+                .unwrap()
+                .pattern_cache
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn other_functions_are_left_to_the_registry() {
+        let mut buf = Vec::new();
+        try_encode_udf(&crate::rlike_udf(), &mut buf).unwrap();
+        assert!(buf.is_empty());
+        assert!(try_decode_udf("rlike", &buf).is_err());
+    }
+
+    #[test]
+    fn bad_payloads_are_rejected() {
+        assert_eq!(
+            decode_config(b"regexp_extract/1").unwrap().cache_size,
+            RegexpExtractConfig::default().cache_size
+        );
+        let err = decode_config(b"regexp_extract/2\ncache_size=1").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected a `regexp_extract/1` payload")
+        );
+        assert!(decode_config(b"regexp_extract/1\ncache_size").is_err());
+        assert!(decode_config(b"regexp_extract/1\nengine=pcre").is_err());
+        assert!(decode_config(&[0xff]).is_err());
+    }
+}
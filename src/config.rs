@@ -1,13 +1,43 @@
-#[derive(Debug, Clone, Copy)]
+use crate::error::{ErrorLog, RegexpExtractError};
+use crate::pattern_cache::SharedPatternCache;
+use std::fmt;
+use std::str::FromStr;
+
+/// What happens when a row cannot be evaluated (invalid pattern, negative `idx`, match error).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum InvalidPatternMode {
+    /// Fail the batch; the message names the row and pattern.
     Error,
+    /// Invalid patterns and match errors give the "no match" result (`""` for
+    /// `regexp_extract`); a negative `idx` still fails.
     EmptyString,
+    /// Offending rows become NULL and are recorded in `RegexpExtractConfig::error_log`.
+    Collect,
+}
+
+/// Output type for `Binary`/`LargeBinary`/`BinaryView` input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOutput {
+    /// Extracted bytes as-is, same width as the input.
+    Binary,
+    /// `Utf8`/`LargeUtf8`/`Utf8View`, invalid UTF-8 replaced with U+FFFD.
+    LossyUtf8,
 }
 
 #[derive(Debug, Clone)]
 pub struct RegexpExtractConfig {
     pub cache_size: usize,
     pub invalid_pattern_mode: InvalidPatternMode,
+    /// Row count from which a batch is split across the rayon pool.
+    /// Only used with the `parallel` feature.
+    pub parallel_threshold: usize,
+    /// Adds `byte_start`/`byte_end` to `regexp_extract_span` results.
+    pub span_byte_offsets: bool,
+    pub binary_output: BinaryOutput,
+    /// Receives the rows nulled by `InvalidPatternMode::Collect`.
+    pub error_log: Option<ErrorLog>,
+    /// Precompiled patterns consulted before compiling.
+    pub pattern_cache: Option<SharedPatternCache>,
 }
 
 impl Default for RegexpExtractConfig {
@@ -15,6 +45,11 @@ impl Default for RegexpExtractConfig {
         Self {
             cache_size: 64,
             invalid_pattern_mode: InvalidPatternMode::Error,
+            parallel_threshold: 65_536,
+            span_byte_offsets: false,
+            binary_output: BinaryOutput::Binary,
+            error_log: None,
+            pattern_cache: None,
         }
     }
 }
@@ -32,4 +67,130 @@ impl RegexpExtractConfig {
         self.invalid_pattern_mode = m;
         self
     }
+
+    pub fn parallel_threshold(mut self, rows: usize) -> Self {
+        self.parallel_threshold = rows;
+        self
+    }
+
+    pub fn span_byte_offsets(mut self, on: bool) -> Self {
+        self.span_byte_offsets = on;
+        self
+    }
+
+    pub fn binary_output(mut self, o: BinaryOutput) -> Self {
+        self.binary_output = o;
+        self
+    }
+
+    pub fn error_log(mut self, log: ErrorLog) -> Self {
+        self.error_log = Some(log);
+        self
+    }
+
+    pub fn pattern_cache(mut self, cache: SharedPatternCache) -> Self {
+        self.pattern_cache = Some(cache);
+        self
+    }
+}
+
+impl FromStr for InvalidPatternMode {
+    type Err = RegexpExtractError;
+
+    /// `error`, `empty_string` or `collect`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "empty_string" => Ok(Self::EmptyString),
+            "collect" => Ok(Self::Collect),
+            _ => Err(invalid(
+                "invalid_pattern_mode",
+                s,
+                "one of error, empty_string, collect",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for InvalidPatternMode {
+    /// The text form accepted by `FromStr`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::EmptyString => "empty_string",
+            Self::Collect => "collect",
+        })
+    }
+}
+
+impl FromStr for BinaryOutput {
+    type Err = RegexpExtractError;
+
+    /// `binary` or `lossy_utf8`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "binary" => Ok(Self::Binary),
+            "lossy_utf8" => Ok(Self::LossyUtf8),
+            _ => Err(invalid("binary_output", s, "one of binary, lossy_utf8")),
+        }
+    }
+}
+
+impl fmt::Display for BinaryOutput {
+    /// The text form accepted by `FromStr`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Binary => "binary",
+            Self::LossyUtf8 => "lossy_utf8",
+        })
+    }
+}
+
+impl RegexpExtractConfig {
+    /// Sets the field named `key` from its text form, e.g. `set("cache_size", "128")`.
+    /// `error_log` and `pattern_cache` have no text form and cannot be set this way.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), RegexpExtractError> {
+        fn parse<T: FromStr>(
+            key: &str,
+            value: &str,
+            expected: &str,
+        ) -> Result<T, RegexpExtractError> {
+            value.parse().map_err(|_| invalid(key, value, expected))
+        }
+        match key {
+            "cache_size" => self.cache_size = parse(key, value, "an integer")?,
+            "invalid_pattern_mode" => self.invalid_pattern_mode = value.parse()?,
+            "parallel_threshold" => self.parallel_threshold = parse(key, value, "an integer")?,
+            "span_byte_offsets" => self.span_byte_offsets = parse(key, value, "true or false")?,
+            "binary_output" => self.binary_output = value.parse()?,
+            _ => {
+                return Err(RegexpExtractError::InvalidConfig {
+                    key: key.to_string(),
+                    message: "unknown option".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Every field `set` accepts, with its current text form.
+    pub fn entries(&self) -> [(&'static str, String); 5] {
+        [
+            ("cache_size", self.cache_size.to_string()),
+            (
+                "invalid_pattern_mode",
+                self.invalid_pattern_mode.to_string(),
+            ),
+            ("parallel_threshold", self.parallel_threshold.to_string()),
+            ("span_byte_offsets", self.span_byte_offsets.to_string()),
+            ("binary_output", self.binary_output.to_string()),
+        ]
+    }
+}
+
+fn invalid(key: &str, value: &str, expected: &str) -> RegexpExtractError {
+    RegexpExtractError::InvalidConfig {
+        key: key.to_string(),
+        message: format!("got `{value}`, expected {expected}"),
+    }
 }
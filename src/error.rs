@@ -1,23 +1,311 @@
 use crate::re::RegexError;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::error::ArrowError;
+use datafusion::common::{DataFusionError, Diagnostic};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+/// Patterns longer than this are cut in error messages.
+const MAX_PATTERN_CHARS: usize = 64;
+
 #[derive(Debug, Error)]
 pub enum RegexpExtractError {
-    #[error("regexp_extract: invalid regex pattern: {0}")]
-    InvalidPattern(#[from] Box<RegexError>),
+    #[error("regexp_extract: invalid regex pattern: {source}")]
+    InvalidPattern {
+        /// The rejected pattern, in full.
+        pattern: String,
+        /// Byte range of `pattern` the engine points at, when it reports a position.
+        span: Option<Range<usize>>,
+        /// Engine that rejected the pattern: `regex`, `regex::bytes` or `fancy-regex`.
+        engine: &'static str,
+        source: Box<RegexError>,
+    },
 
     #[error("regexp_extract: idx must be >= 0, got {0}")]
     NegativeIndex(i64),
 
+    /// A group index the pattern does not have (Spark's `REGEX_GROUP_INDEX` error), raised
+    /// by `func`.
+    #[error("{func}: regex group count is {groups}, but the specified group index is {idx}")]
+    GroupIndexOutOfRange {
+        func: &'static str,
+        idx: i64,
+        groups: usize,
+    },
+
     #[error("regexp_extract: idx array missing (internal)")]
     MissingIdxArray,
 
+    /// A kernel was handed an input type its caller should have routed elsewhere.
+    #[error("regexp_extract: unexpected input type {0} (internal)")]
+    UnexpectedInputType(DataType),
+
+    /// A pattern or `idx` array that is neither one value per row nor a single value.
+    #[error("regexp_extract: {argument} has {len} values for {rows} rows")]
+    LengthMismatch {
+        argument: &'static str,
+        rows: usize,
+        len: usize,
+    },
+
     #[error("regexp_extract: match error: {0}")]
     MatchError(String),
+
+    #[error("regexp_extract: pattern not supported for binary input: {0}")]
+    UnsupportedBinaryPattern(String),
+
+    #[error("regexp_extract: invalid config `{key}`: {message}")]
+    InvalidConfig { key: String, message: String },
+
+    #[error("regexp_classify: got {labels} labels for {patterns} patterns")]
+    LabelCountMismatch { patterns: usize, labels: usize },
+
+    /// Assembling the output failed; not tied to a row. Reaches DataFusion as
+    /// `DataFusionError::ArrowError`.
+    #[error(transparent)]
+    Arrow(#[from] ArrowError),
+
+    /// `source` located at a row of the evaluated batch (`None` for a scalar pattern
+    /// rejected before any row) and its pattern, truncated.
+    #[error("{source} ({})", location(*.row, .pattern))]
+    AtRow {
+        row: Option<usize>,
+        pattern: String,
+        source: Box<RegexpExtractError>,
+    },
+}
+
+fn location(row: Option<usize>, pattern: &str) -> String {
+    match row {
+        Some(row) => format!("row {row}, pattern `{pattern}`"),
+        None => format!("pattern `{pattern}`"),
+    }
 }
 
-impl From<RegexError> for RegexpExtractError {
-    fn from(e: RegexError) -> Self {
-        Self::InvalidPattern(Box::new(e))
+fn truncate_pattern(pattern: &str) -> String {
+    match pattern.char_indices().nth(MAX_PATTERN_CHARS) {
+        Some((end, _)) => format!("{}…", &pattern[..end]),
+        None => pattern.to_string(),
+    }
+}
+
+impl RegexpExtractError {
+    /// Adds the failing row and (truncated) pattern to the message.
+    pub fn at_row(self, row: Option<usize>, pattern: &str) -> Self {
+        Self::AtRow {
+            row,
+            pattern: truncate_pattern(pattern),
+            source: Box::new(self),
+        }
+    }
+
+    pub fn kind(&self) -> RowErrorKind {
+        match self {
+            Self::InvalidPattern { .. } | Self::UnsupportedBinaryPattern(_) => {
+                RowErrorKind::InvalidPattern
+            }
+            Self::NegativeIndex(_) => RowErrorKind::NegativeIndex,
+            Self::GroupIndexOutOfRange { .. } => RowErrorKind::GroupIndexOutOfRange,
+            Self::MatchError(_) => RowErrorKind::MatchError,
+            Self::AtRow { source, .. } => source.kind(),
+            Self::MissingIdxArray
+            | Self::UnexpectedInputType(_)
+            | Self::LengthMismatch { .. }
+            | Self::InvalidConfig { .. }
+            | Self::LabelCountMismatch { .. }
+            | Self::Arrow(_) => RowErrorKind::Other,
+        }
+    }
+
+    /// Row of the evaluated batch the error was raised for.
+    pub fn row(&self) -> Option<usize> {
+        match self {
+            Self::AtRow { row, .. } => *row,
+            _ => None,
+        }
+    }
+
+    /// Full text of the rejected pattern.
+    pub fn pattern(&self) -> Option<&str> {
+        match self.invalid_pattern()? {
+            Self::InvalidPattern { pattern, .. } => Some(pattern),
+            _ => None,
+        }
+    }
+
+    /// Byte range of [`pattern`](Self::pattern) the engine points at.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self.invalid_pattern()? {
+            Self::InvalidPattern { span, .. } => span.clone(),
+            _ => None,
+        }
+    }
+
+    /// Engine that rejected the pattern.
+    pub fn engine(&self) -> Option<&'static str> {
+        match self.invalid_pattern()? {
+            Self::InvalidPattern { engine, .. } => Some(engine),
+            _ => None,
+        }
+    }
+
+    fn invalid_pattern(&self) -> Option<&Self> {
+        match self {
+            Self::InvalidPattern { .. } => Some(self),
+            Self::AtRow { source, .. } => source.invalid_pattern(),
+            _ => None,
+        }
+    }
+
+    /// The pattern with a caret line under [`span`](Self::span):
+    ///
+    /// ```text
+    /// ab(c
+    ///   ^
+    /// ```
+    pub fn caret(&self) -> Option<String> {
+        let (pattern, span) = (self.pattern()?, self.span()?);
+        let pad = pattern.get(..span.start)?.chars().count();
+        let width = pattern.get(span)?.chars().count().max(1);
+        Some(format!(
+            "{pattern}\n{}{}",
+            " ".repeat(pad),
+            "^".repeat(width)
+        ))
+    }
+
+    /// DataFusion diagnostic rendering the caret under the bad pattern span.
+    pub fn diagnostic(&self) -> Option<Diagnostic> {
+        let caret = self.caret()?;
+        let mut d = Diagnostic::new_error(self.to_string(), None).with_note(caret, None);
+        if let Some(engine) = self.engine() {
+            d.add_help(format!("pattern rejected by the `{engine}` engine"), None);
+        }
+        Some(d)
+    }
+
+    /// Finds the `RegexpExtractError` behind a `DataFusionError` returned by a query.
+    pub fn from_datafusion(err: &DataFusionError) -> Option<&Self> {
+        let mut cur: &(dyn std::error::Error + 'static) = err;
+        loop {
+            if let Some(e) = cur.downcast_ref::<Self>() {
+                return Some(e);
+            }
+            cur = cur.source()?;
+        }
+    }
+}
+
+/// Kept as [`DataFusionError::External`] so callers can downcast (see
+/// [`RegexpExtractError::from_datafusion`]), with a [`Diagnostic`] when a span is known.
+impl From<RegexpExtractError> for DataFusionError {
+    fn from(e: RegexpExtractError) -> Self {
+        let e = match e {
+            RegexpExtractError::Arrow(e) => return DataFusionError::ArrowError(Box::new(e), None),
+            e => e,
+        };
+        let diagnostic = e.diagnostic();
+        let err = DataFusionError::External(Box::new(e));
+        match diagnostic {
+            Some(d) => err.with_diagnostic(d),
+            None => err,
+        }
+    }
+}
+
+/// Which check failed for a row reported under `InvalidPatternMode::Collect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RowErrorKind {
+    InvalidPattern,
+    NegativeIndex,
+    GroupIndexOutOfRange,
+    MatchError,
+    Other,
+}
+
+/// A row that produced NULL instead of failing the batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Row index within the evaluated batch.
+    pub row: usize,
+    /// 0-based ordinal of that batch among the evaluations logging here, in the order they
+    /// started. Every call in a query counts, so it is the input batch only when one call
+    /// uses the log, and follows input order only with `target_partitions = 1`. `None` for
+    /// errors raised outside a batch (invalid `regexp_classify` rules).
+    pub batch: Option<u64>,
+    pub kind: RowErrorKind,
+    /// Pattern of the row, truncated like in error messages.
+    pub pattern: String,
+    pub message: String,
+}
+
+impl RowError {
+    pub fn new(row: usize, pattern: &str, err: &RegexpExtractError) -> Self {
+        Self {
+            row,
+            batch: None,
+            kind: err.kind(),
+            pattern: truncate_pattern(pattern),
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Side channel receiving every [`RowError`] of UDFs whose config holds it.
+///
+/// Clones share the same log, so keep one handle and [`take`](Self::take) after a query.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorLog(Arc<Mutex<LogState>>);
+
+#[derive(Debug, Default)]
+struct LogState {
+    errors: Vec<RowError>,
+    /// Batches started so far; the next batch gets this ordinal.
+    batches: u64,
+}
+
+impl ErrorLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drains the recorded errors. Each batch's errors are appended together once it is
+    /// evaluated, in row order; batches come in the order they finished, which with
+    /// several partitions is not their [`RowError::batch`] order.
+    pub fn take(&self) -> Vec<RowError> {
+        std::mem::take(&mut self.state().errors)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state().errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn push(&self, e: RowError) {
+        self.state().errors.push(e);
+    }
+
+    /// Ordinal for a batch about to be evaluated.
+    pub(crate) fn next_batch(&self) -> u64 {
+        let mut state = self.state();
+        state.batches += 1;
+        state.batches - 1
+    }
+
+    /// Appends the errors of batch `batch`, sorted by row.
+    pub(crate) fn push_batch(&self, batch: u64, mut errors: Vec<RowError>) {
+        errors.sort_by_key(|e| e.row);
+        for e in &mut errors {
+            e.batch = Some(batch);
+        }
+        self.state().errors.extend(errors);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, LogState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
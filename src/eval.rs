@@ -1,10 +1,14 @@
+use crate::binary::{is_binary, run_binary};
 use crate::config::RegexpExtractConfig;
-use crate::kernel::{
-    run_large_utf8_largeutf8, run_large_utf8_utf8, run_utf8_largeutf8, run_utf8_utf8,
-};
+use crate::error::RegexpExtractError;
+use crate::kernel::{StrArray, in_batch, run_generic, run_with_sink};
+use crate::span::SpanSink;
 use crate::types::to_array;
-use datafusion::arrow::array::{ArrayRef, Int32Array, Int64Array, LargeStringArray, StringArray};
+use datafusion::arrow::array::{ArrayRef, Int32Array, Int64Array};
 use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::{
+    as_int32_array, as_int64_array, as_large_string_array, as_string_array,
+};
 use datafusion::common::{DataFusionError, Result};
 use datafusion::logical_expr::{ColumnarValue, ScalarFunctionArgs};
 
@@ -12,12 +16,44 @@ pub fn evaluate_regexp_extract_with(
     args: ScalarFunctionArgs,
     cfg: &RegexpExtractConfig,
 ) -> Result<ColumnarValue> {
+    in_batch(cfg, |cfg| evaluate(args, cfg, Output::Text))
+}
+
+/// `regexp_extract_span`: same arguments, `Struct{value, start, end}` per row.
+pub fn evaluate_regexp_extract_span_with(
+    args: ScalarFunctionArgs,
+    cfg: &RegexpExtractConfig,
+) -> Result<ColumnarValue> {
+    in_batch(cfg, |cfg| evaluate(args, cfg, Output::Span))
+}
+
+#[derive(Clone, Copy)]
+enum Output {
+    Text,
+    Span,
+}
+
+impl Output {
+    fn name(self) -> &'static str {
+        match self {
+            Output::Text => "regexp_extract",
+            Output::Span => "regexp_extract_span",
+        }
+    }
+}
+
+fn evaluate(
+    args: ScalarFunctionArgs,
+    cfg: &RegexpExtractConfig,
+    output: Output,
+) -> Result<ColumnarValue> {
+    let name = output.name();
     let rows = args.number_rows;
     let a = &args.args;
 
     if a.len() != 3 {
         return Err(DataFusionError::Execution(format!(
-            "regexp_extract expects 3 arguments, got {}",
+            "{name} expects 3 arguments, got {}",
             a.len()
         )));
     }
@@ -34,98 +70,58 @@ pub fn evaluate_regexp_extract_with(
         _ => to_array(&a[2], rows)?,
     };
 
-    // Determine output width from first arg
-    let out_dt = s_arr.data_type().clone();
-
-    // Downcast pattern and idx
-    let pat_utf8: Option<&StringArray> = (p_arr.data_type() == &DataType::Utf8)
-        .then(|| p_arr.as_any().downcast_ref::<StringArray>().unwrap());
-
-    let pat_lutf8: Option<&LargeStringArray> = (p_arr.data_type() == &DataType::LargeUtf8)
-        .then(|| p_arr.as_any().downcast_ref::<LargeStringArray>().unwrap());
-
-    if pat_utf8.is_none() && pat_lutf8.is_none() {
-        return Err(DataFusionError::Execution(
-            "regexp_extract pattern must be Utf8 or LargeUtf8".into(),
-        ));
+    if !matches!(p_arr.data_type(), DataType::Utf8 | DataType::LargeUtf8) {
+        return Err(DataFusionError::Execution(format!(
+            "{name} pattern must be Utf8 or LargeUtf8"
+        )));
     }
 
     let (idx_i64, idx_i32): (Option<&Int64Array>, Option<&Int32Array>) = match i_arr.data_type() {
-        DataType::Int64 => (
-            Some(i_arr.as_any().downcast_ref::<Int64Array>().unwrap()),
-            None,
-        ),
-        DataType::Int32 => (
-            None,
-            Some(i_arr.as_any().downcast_ref::<Int32Array>().unwrap()),
-        ),
+        DataType::Int64 => (Some(as_int64_array(&i_arr)?), None),
+        DataType::Int32 => (None, Some(as_int32_array(&i_arr)?)),
         other => {
             return Err(DataFusionError::Execution(format!(
-                "regexp_extract idx must be Int32 or Int64, got {other:?}"
+                "{name} idx must be Int32 or Int64, got {other:?}"
             )));
         }
     };
 
     let out: ArrayRef = match (s_arr.data_type(), p_arr.data_type()) {
+        (dt, _) if is_binary(dt) && matches!(output, Output::Span) => {
+            return Err(DataFusionError::Execution(format!(
+                "{name} does not support binary input, got {dt:?}"
+            )));
+        }
+        (dt, DataType::Utf8) if is_binary(dt) => {
+            let p = as_string_array(&p_arr)?;
+            run_binary(s_arr.as_ref(), p, idx_i64, idx_i32, cfg).map_err(DataFusionError::from)?
+        }
+        (dt, _) if is_binary(dt) => {
+            let p = as_large_string_array(&p_arr)?;
+            run_binary(s_arr.as_ref(), p, idx_i64, idx_i32, cfg).map_err(DataFusionError::from)?
+        }
         (DataType::Utf8, DataType::Utf8) => {
-            let s = s_arr.as_any().downcast_ref::<StringArray>().unwrap();
-            let p = pat_utf8.unwrap();
-            run_utf8_utf8(
-                s,
-                p,
-                idx_i64,
-                idx_i32,
-                &out_dt,
-                cfg.cache_size,
-                cfg.invalid_pattern_mode,
-            )
-            .map_err(|e| DataFusionError::Execution(e.to_string()))?
+            let (s, p) = (as_string_array(&s_arr)?, as_string_array(&p_arr)?);
+            run(s, p, idx_i64, idx_i32, cfg, output).map_err(DataFusionError::from)?
         }
         (DataType::LargeUtf8, DataType::Utf8) => {
-            let s = s_arr.as_any().downcast_ref::<LargeStringArray>().unwrap();
-            let p = pat_utf8.unwrap();
-            run_large_utf8_utf8(
-                s,
-                p,
-                idx_i64,
-                idx_i32,
-                &out_dt,
-                cfg.cache_size,
-                cfg.invalid_pattern_mode,
-            )
-            .map_err(|e| DataFusionError::Execution(e.to_string()))?
+            let (s, p) = (as_large_string_array(&s_arr)?, as_string_array(&p_arr)?);
+            run(s, p, idx_i64, idx_i32, cfg, output).map_err(DataFusionError::from)?
         }
-        (DataType::Utf8, DataType::LargeUtf8) => {
-            let s = s_arr.as_any().downcast_ref::<StringArray>().unwrap();
-            let p = pat_lutf8.unwrap();
-            run_utf8_largeutf8(
-                s,
-                p,
-                idx_i64,
-                idx_i32,
-                &out_dt,
-                cfg.cache_size,
-                cfg.invalid_pattern_mode,
-            )
-            .map_err(|e| DataFusionError::Execution(e.to_string()))?
+        (DataType::Utf8, _) => {
+            let (s, p) = (as_string_array(&s_arr)?, as_large_string_array(&p_arr)?);
+            run(s, p, idx_i64, idx_i32, cfg, output).map_err(DataFusionError::from)?
         }
-        (DataType::LargeUtf8, DataType::LargeUtf8) => {
-            let s = s_arr.as_any().downcast_ref::<LargeStringArray>().unwrap();
-            let p = pat_lutf8.unwrap();
-            run_large_utf8_largeutf8(
-                s,
-                p,
-                idx_i64,
-                idx_i32,
-                &out_dt,
-                cfg.cache_size,
-                cfg.invalid_pattern_mode,
-            )
-            .map_err(|e| DataFusionError::Execution(e.to_string()))?
+        (DataType::LargeUtf8, _) => {
+            let (s, p) = (
+                as_large_string_array(&s_arr)?,
+                as_large_string_array(&p_arr)?,
+            );
+            run(s, p, idx_i64, idx_i32, cfg, output).map_err(DataFusionError::from)?
         }
         (other_s, _) => {
             return Err(DataFusionError::Execution(format!(
-                "regexp_extract expects first argument Utf8 or LargeUtf8, got {other_s:?}"
+                "{name} expects first argument Utf8, LargeUtf8 or binary, got {other_s:?}"
             )));
         }
     };
@@ -133,6 +129,26 @@ pub fn evaluate_regexp_extract_with(
     Ok(ColumnarValue::Array(out))
 }
 
+fn run<S, P>(
+    strings: &S,
+    patterns: &P,
+    idx_i64: Option<&Int64Array>,
+    idx_i32: Option<&Int32Array>,
+    cfg: &RegexpExtractConfig,
+    output: Output,
+) -> Result<ArrayRef, RegexpExtractError>
+where
+    S: StrArray + Sync,
+    P: StrArray + Sync,
+{
+    match output {
+        Output::Text => run_generic(strings, patterns, idx_i64, idx_i32, cfg),
+        Output::Span => {
+            run_with_sink::<S, P, SpanSink<S::Builder>>(strings, patterns, idx_i64, idx_i32, cfg)
+        }
+    }
+}
+
 pub fn evaluate_regexp_extract(args: ScalarFunctionArgs) -> Result<ColumnarValue> {
     evaluate_regexp_extract_with(args, &RegexpExtractConfig::default())
 }
@@ -0,0 +1,76 @@
+//! `datafusion-ffi` export of `regexp_extract`, for loading the UDF as a plugin into a
+//! DataFusion binary built with another compiler.
+//!
+//! With the `ffi` feature this module defines the `abi_stable` root module,
+//! [`RegexpExtractModule`], and [`module`] builds it. The `ffi-plugin` workspace member
+//! is the `cdylib` exporting it, so this crate stays an `rlib`. A host loads the plugin with
+//! `RegexpExtractModuleRef::load_from_directory(dir)`, calls `create_udf` (or
+//! `create_udf_with` and a config in the `codec` payload format) and wraps the returned
+//! `FFI_ScalarUDF` in a `ForeignScalarUDF`:
+//!
+//! ```text
+//! let module = RegexpExtractModuleRef::load_from_directory(dir)?;
+//! let ffi_udf = module.create_udf().unwrap()();
+//! let udf = ScalarUDF::new_from_impl(ForeignScalarUDF::try_from(&ffi_udf)?);
+//! ctx.register_udf(udf);
+//! ```
+//!
+//! The UDF runs inside the plugin with its own copy of this crate, so an `ErrorLog` or
+//! `SharedPatternCache` of the host cannot be attached to it; the patterns of a warmed
+//! cache in the payload are warmed again inside the plugin.
+
+use crate::codec::decode_config;
+use crate::udf::{regexp_extract_udf, regexp_extract_udf_with};
+use abi_stable::library::{LibraryError, RootModule};
+use abi_stable::prefix_type::PrefixTypeTrait;
+use abi_stable::sabi_types::VersionStrings;
+use abi_stable::std_types::{RResult, RStr, RString};
+use abi_stable::{StableAbi, declare_root_module_statics, package_version_strings};
+use datafusion_ffi::udf::FFI_ScalarUDF;
+use std::sync::Arc;
+
+/// Functions exported by the plugin.
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix(prefix_ref = RegexpExtractModuleRef)))]
+pub struct RegexpExtractModule {
+    /// `regexp_extract` with the default config.
+    pub create_udf: extern "C" fn() -> FFI_ScalarUDF,
+
+    /// `regexp_extract` with the config encoded by `codec::encode_config`; an error
+    /// message if it does not decode.
+    #[sabi(last_prefix_field)]
+    pub create_udf_with: extern "C" fn(config: RStr<'_>) -> RResult<FFI_ScalarUDF, RString>,
+}
+
+impl RootModule for RegexpExtractModuleRef {
+    declare_root_module_statics! {RegexpExtractModuleRef}
+    /// Library name of the `ffi-plugin` crate.
+    const BASE_NAME: &'static str = "datafusion_regexp_extract_udf_plugin";
+    const NAME: &'static str = "datafusion_regexp_extract_udf";
+    const VERSION_STRINGS: VersionStrings = package_version_strings!();
+
+    fn initialization(self) -> Result<Self, LibraryError> {
+        Ok(self)
+    }
+}
+
+extern "C" fn create_udf() -> FFI_ScalarUDF {
+    Arc::new(regexp_extract_udf()).into()
+}
+
+extern "C" fn create_udf_with(config: RStr<'_>) -> RResult<FFI_ScalarUDF, RString> {
+    match decode_config(config.as_str().as_bytes()) {
+        Ok(cfg) => RResult::ROk(Arc::new(regexp_extract_udf_with(cfg)).into()),
+        Err(e) => RResult::RErr(e.to_string().into()),
+    }
+}
+
+/// The root module, for a `cdylib` to return from its `#[export_root_module]` function.
+pub fn module() -> RegexpExtractModuleRef {
+    RegexpExtractModule {
+        create_udf,
+        create_udf_with,
+    }
+    .leak_into_prefix()
+}
@@ -1,27 +1,89 @@
-use crate::config::InvalidPatternMode;
-use crate::error::RegexpExtractError;
-use crate::pattern_cache::PatternCache;
-use crate::re::{Regex, captures, compile};
+use crate::config::{InvalidPatternMode, RegexpExtractConfig};
+use crate::error::{ErrorLog, RegexpExtractError, RowError};
+use crate::pattern_cache::{CompiledPattern, PatternCache};
+use crate::prefilter::Prefilter;
+use crate::re::{CaptureBuf, Regex, RegexError, capture_buf, captures_read, find_at};
 use datafusion::arrow::array::{
     Array, ArrayRef, Int32Array, Int64Array, LargeStringArray, LargeStringBuilder, StringArray,
     StringBuilder,
 };
 use datafusion::arrow::datatypes::DataType;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 
+/// Column patterns are grouped by value when a range has at least this many rows...
+const GROUPED_MIN_ROWS: usize = 256;
+/// ...and at most one distinct pattern per this many rows.
+const GROUPED_DISTINCT_RATIO: usize = 8;
+
+/// Smallest row range worth handing to a rayon worker.
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL_CHUNK: usize = 4096;
+
 /// Minimal traits to unify Utf8 and LargeUtf8 arrays/builders without runtime dispatch.
-trait StrArray: Array {
+pub(crate) trait StrArray: Array {
     type Builder: StrBuilder;
     fn value(&self, i: usize) -> &str;
     fn builder_with_capacity(len: usize, bytes: usize) -> Self::Builder;
+    fn candidates(&self, pf: &Prefilter) -> Vec<bool>;
 }
 
-trait StrBuilder {
+pub(crate) trait StrBuilder {
     fn append_null(&mut self);
     fn append_value(&mut self, v: &str);
     fn finish_array(&mut self) -> ArrayRef; // renamed to avoid name clash with inherent `finish`
 }
 
+/// Receives one result per row, so the same kernel can emit extracted text or spans.
+pub(crate) trait RowSink<S: StrArray>: Sized {
+    fn with_capacity(rows: usize, bytes: usize, cfg: &RegexpExtractConfig) -> Self;
+    fn append_null(&mut self);
+    /// `span` is the byte range of the selected group within `s`; `None` when nothing
+    /// matched, the group did not participate or the pattern is invalid (`EmptyString`).
+    fn append_match(&mut self, s: &str, span: Option<(usize, usize)>);
+    fn finish_array(self) -> ArrayRef;
+
+    /// A row nulled under `InvalidPatternMode::Collect`.
+    fn append_error(&mut self, _e: &RowError) {
+        self.append_null()
+    }
+
+    /// Writes the result of a failed row, or returns the error that aborts the batch.
+    fn append_failure(&mut self, s: &str, f: RowFailure) -> Result<(), RegexpExtractError> {
+        match f {
+            RowFailure::NoMatch => self.append_match(s, None),
+            RowFailure::Null(e) => self.append_error(&e),
+            RowFailure::Fail(e) => return Err(e),
+        }
+        Ok(())
+    }
+}
+
+/// `regexp_extract` output: the group text, `""` when there is none.
+pub(crate) struct TextSink<B>(B);
+
+impl<S: StrArray> RowSink<S> for TextSink<S::Builder> {
+    #[inline]
+    fn with_capacity(rows: usize, bytes: usize, _cfg: &RegexpExtractConfig) -> Self {
+        TextSink(S::builder_with_capacity(rows, bytes))
+    }
+
+    #[inline]
+    fn append_null(&mut self) {
+        self.0.append_null()
+    }
+
+    #[inline]
+    fn append_match(&mut self, s: &str, span: Option<(usize, usize)>) {
+        self.0.append_value(span.map_or("", |(a, b)| &s[a..b]))
+    }
+
+    fn finish_array(mut self) -> ArrayRef {
+        self.0.finish_array()
+    }
+}
+
 // --- Trait impls for Utf8 ---
 
 impl StrArray for StringArray {
@@ -36,6 +98,11 @@ impl StrArray for StringArray {
     fn builder_with_capacity(len: usize, bytes: usize) -> Self::Builder {
         StringBuilder::with_capacity(len, bytes)
     }
+
+    #[inline]
+    fn candidates(&self, pf: &Prefilter) -> Vec<bool> {
+        pf.candidates(self.value_data(), self.value_offsets())
+    }
 }
 
 impl StrBuilder for StringBuilder {
@@ -69,6 +136,11 @@ impl StrArray for LargeStringArray {
     fn builder_with_capacity(len: usize, bytes: usize) -> Self::Builder {
         LargeStringBuilder::with_capacity(len, bytes)
     }
+
+    #[inline]
+    fn candidates(&self, pf: &Prefilter) -> Vec<bool> {
+        pf.candidates(self.value_data(), self.value_offsets())
+    }
 }
 
 impl StrBuilder for LargeStringBuilder {
@@ -89,237 +161,700 @@ impl StrBuilder for LargeStringBuilder {
     }
 }
 
+/// How evaluation continues after a row failed, per `cfg.invalid_pattern_mode`.
+pub(crate) enum RowFailure {
+    /// `EmptyString`: emit the function's "no match" result.
+    NoMatch,
+    /// `Collect`: the error is logged; emit NULL.
+    Null(RowError),
+    /// `Error`: abort the batch.
+    Fail(RegexpExtractError),
+}
+
+impl RowFailure {
+    /// Value to emit for the row (`no_match` or `null`), or the error aborting the batch.
+    pub(crate) fn resolve<T>(self, no_match: T, null: T) -> Result<T, RegexpExtractError> {
+        match self {
+            Self::NoMatch => Ok(no_match),
+            Self::Null(_) => Ok(null),
+            Self::Fail(e) => Err(e),
+        }
+    }
+}
+
+/// Classifies the failure of row `row` (evaluated with `pattern`).
+pub(crate) fn row_failure(
+    cfg: &RegexpExtractConfig,
+    row: usize,
+    pattern: &str,
+    err: RegexpExtractError,
+) -> RowFailure {
+    match cfg.invalid_pattern_mode {
+        // a bad idx is a query bug, not bad data: never silently ""
+        InvalidPatternMode::EmptyString
+            if !matches!(
+                err,
+                RegexpExtractError::NegativeIndex(_)
+                    | RegexpExtractError::GroupIndexOutOfRange { .. }
+            ) =>
+        {
+            RowFailure::NoMatch
+        }
+        InvalidPatternMode::Collect => {
+            RowFailure::Null(collect(cfg, RowError::new(row, pattern, &err)))
+        }
+        _ => RowFailure::Fail(err.at_row(Some(row), pattern)),
+    }
+}
+
+/// Runs the evaluation of one batch. With an error log, the batch collects into a log of
+/// its own, which is appended to the shared one sorted by row and tagged with the batch
+/// ordinal, so parallel ranges and grouped patterns cannot reorder it.
+pub(crate) fn in_batch<T>(
+    cfg: &RegexpExtractConfig,
+    f: impl FnOnce(&RegexpExtractConfig) -> T,
+) -> T {
+    let Some(log) = &cfg.error_log else {
+        return f(cfg);
+    };
+    let batch = log.next_batch();
+    let local = ErrorLog::new();
+    let out = f(&cfg.clone().error_log(local.clone()));
+    log.push_batch(batch, local.take());
+    out
+}
+
+fn collect(cfg: &RegexpExtractConfig, e: RowError) -> RowError {
+    if let Some(log) = &cfg.error_log {
+        log.push(e.clone());
+    }
+    e
+}
+
+/// A pattern compiled once for many rows (scalar pattern, or one group of a column).
+pub(crate) enum ScalarPattern<R> {
+    Compiled(R),
+    /// Invalid under `EmptyString`: rows get the "no match" result.
+    NoMatch,
+    /// Invalid under `Collect`: rows are NULL and reported with this error.
+    Invalid(RowError),
+}
+
+impl<R: CompiledPattern> ScalarPattern<R> {
+    /// Compiles a batch-wide pattern; under `Error` an invalid one fails before any row.
+    pub(crate) fn compile(
+        pat: &str,
+        cfg: &RegexpExtractConfig,
+    ) -> Result<Self, RegexpExtractError> {
+        match R::compile_with(cfg.pattern_cache.as_ref(), pat) {
+            Ok(re) => Ok(Self::Compiled(re)),
+            Err(e) => match cfg.invalid_pattern_mode {
+                InvalidPatternMode::Error => Err(e.at_row(None, pat)),
+                InvalidPatternMode::EmptyString => Ok(Self::NoMatch),
+                InvalidPatternMode::Collect => Ok(Self::Invalid(RowError::new(0, pat, &e))),
+            },
+        }
+    }
+}
+
+impl<R> ScalarPattern<R> {
+    #[inline]
+    pub(crate) fn regex(&self) -> Option<&R> {
+        match self {
+            Self::Compiled(re) => Some(re),
+            _ => None,
+        }
+    }
+
+    /// Result for row `row` when the pattern is invalid.
+    pub(crate) fn failure(&self, row: usize, cfg: &RegexpExtractConfig) -> RowFailure {
+        match self {
+            Self::Invalid(e) => RowFailure::Null(collect(cfg, RowError { row, ..e.clone() })),
+            _ => RowFailure::NoMatch,
+        }
+    }
+}
+
+/// Row result buffered by the grouped strategy before it is written in row order.
+#[derive(Clone)]
+enum Slot {
+    Null,
+    Match(Option<(usize, usize)>),
+    Error(RowError),
+}
+
 /// Generic kernel: works for any string width combinations and returns an array
 /// whose width matches `strings` (builder is `S::Builder`).
-fn run_generic<S, P>(
+pub(crate) fn run_generic<S, P>(
     strings: &S,
     patterns: &P,
     idx_i64: Option<&Int64Array>,
     idx_i32: Option<&Int32Array>,
-    cache_cap: usize,
-    mode: InvalidPatternMode,
+    cfg: &RegexpExtractConfig,
+) -> Result<ArrayRef, RegexpExtractError>
+where
+    S: StrArray + Sync,
+    P: StrArray + Sync,
+{
+    run_with_sink::<S, P, TextSink<S::Builder>>(strings, patterns, idx_i64, idx_i32, cfg)
+}
+
+/// Shared driver of `run_generic` and the span kernel: `O` decides what is written per row.
+///
+/// With the `parallel` feature, batches of at least `cfg.parallel_threshold` rows are
+/// split into row ranges evaluated on the rayon pool and concatenated in order.
+pub(crate) fn run_with_sink<S, P, O>(
+    strings: &S,
+    patterns: &P,
+    idx_i64: Option<&Int64Array>,
+    idx_i32: Option<&Int32Array>,
+    cfg: &RegexpExtractConfig,
 ) -> Result<ArrayRef, RegexpExtractError>
+where
+    S: StrArray + Sync,
+    P: StrArray + Sync,
+    O: RowSink<S>,
+{
+    let n = strings.len();
+    let batch = Batch::new(strings, patterns, idx_i64, idx_i32, cfg)?;
+
+    #[cfg(feature = "parallel")]
+    if n >= cfg.parallel_threshold.max(1) {
+        return batch.run_parallel::<O>(n);
+    }
+
+    batch.run_range::<O>(0..n)
+}
+
+/// Per-batch state shared by every row range: the scalar pattern is compiled (and
+/// prefiltered) once, while caches, capture buffers and builders stay per range.
+struct Batch<'a, S, P> {
+    strings: &'a S,
+    patterns: &'a P,
+    idx_i64: Option<&'a Int64Array>,
+    idx_i32: Option<&'a Int32Array>,
+    cfg: &'a RegexpExtractConfig,
+    pat_scalar: bool,
+    idx_is_scalar: bool,
+    idx_scalar: i64,
+    use_no_nulls_fast_path: bool,
+    /// Compiled scalar pattern; `None` for column patterns.
+    scalar: Option<ScalarPattern<Regex>>,
+    candidates: Option<Vec<bool>>,
+}
+
+impl<'a, S, P> Batch<'a, S, P>
 where
     S: StrArray,
     P: StrArray,
 {
-    let n = strings.len();
+    fn new(
+        strings: &'a S,
+        patterns: &'a P,
+        idx_i64: Option<&'a Int64Array>,
+        idx_i32: Option<&'a Int32Array>,
+        cfg: &'a RegexpExtractConfig,
+    ) -> Result<Self, RegexpExtractError> {
+        let idx_len = idx_i64.map(Array::len).or(idx_i32.map(Array::len));
+        let idx_len = idx_len.ok_or(RegexpExtractError::MissingIdxArray)?;
+        check_lengths(
+            strings.len(),
+            &[("pattern", patterns.len()), ("idx", idx_len)],
+        )?;
+
+        // Detect scalar idx once (moved above estimate_bytes)
+        let (idx_is_scalar, idx_scalar) = match (idx_i64, idx_i32) {
+            (Some(i64s), None) if i64s.len() == 1 => (true, i64s.value(0)),
+            (None, Some(i32s)) if i32s.len() == 1 => (true, i32s.value(0) as i64),
+            _ => (false, 0),
+        };
 
-    // Detect scalar idx once (moved above estimate_bytes)
-    let (idx_is_scalar, idx_scalar) = match (idx_i64, idx_i32) {
-        (Some(i64s), None) if i64s.len() == 1 => (true, i64s.value(0)),
-        (None, Some(i32s)) if i32s.len() == 1 => (true, i32s.value(0) as i64),
-        _ => (false, 0),
-    };
+        let pat_scalar = patterns.len() == 1;
 
-    let bytes_hint = estimate_bytes(strings, idx_i64, idx_i32, idx_is_scalar, idx_scalar);
-    let mut b = S::builder_with_capacity(n, bytes_hint);
-    let mut cache = PatternCache::new(cache_cap);
+        let str_no_nulls = strings.null_count() == 0;
+        let pat_no_nulls = if pat_scalar {
+            !patterns.is_null(0)
+        } else {
+            patterns.null_count() == 0
+        };
+        let idx_no_nulls = if idx_is_scalar {
+            match (idx_i64, idx_i32) {
+                (Some(i64s), None) => !i64s.is_null(0),
+                (None, Some(i32s)) => !i32s.is_null(0),
+                _ => false, // shouldn't happen
+            }
+        } else if let Some(i64s) = idx_i64 {
+            i64s.null_count() == 0
+        } else if let Some(i32s) = idx_i32 {
+            i32s.null_count() == 0
+        } else {
+            false
+        };
 
-    let mut compiled_scalar: Option<Regex> = None;
-    let mut scalar_pat_invalid = false;
+        let use_no_nulls_fast_path = str_no_nulls && pat_no_nulls && idx_no_nulls;
 
-    let pat_scalar = patterns.len() == 1;
+        // compile scalar pattern once (column patterns go through the per-range cache)
+        let scalar = if pat_scalar {
+            Some(ScalarPattern::compile(patterns.value(0), cfg)?)
+        } else {
+            None
+        };
+        let compiled = scalar.as_ref().is_some_and(|p| p.regex().is_some());
+        let candidates = scalar_candidates(strings, patterns, compiled);
+
+        Ok(Self {
+            strings,
+            patterns,
+            idx_i64,
+            idx_i32,
+            cfg,
+            pat_scalar,
+            idx_is_scalar,
+            idx_scalar,
+            use_no_nulls_fast_path,
+            scalar,
+            candidates,
+        })
+    }
 
-    let str_no_nulls = strings.null_count() == 0;
-    let pat_no_nulls = if pat_scalar {
-        !patterns.is_null(0)
-    } else {
-        patterns.null_count() == 0
-    };
-    let idx_no_nulls = if idx_is_scalar {
-        match (idx_i64, idx_i32) {
-            (Some(i64s), None) => !i64s.is_null(0),
-            (None, Some(i32s)) => !i32s.is_null(0),
-            _ => false, // shouldn't happen
+    #[cfg(feature = "parallel")]
+    fn run_parallel<O: RowSink<S>>(&self, n: usize) -> Result<ArrayRef, RegexpExtractError>
+    where
+        S: Sync,
+        P: Sync,
+    {
+        use datafusion::arrow::compute::concat;
+        use rayon::prelude::*;
+
+        let chunk = n
+            .div_ceil(rayon::current_num_threads())
+            .max(MIN_PARALLEL_CHUNK);
+        let ranges: Vec<_> = (0..n)
+            .step_by(chunk)
+            .map(|s| s..(s + chunk).min(n))
+            .collect();
+
+        // Each range stops at its first error, so the earliest range's error is the
+        // first one in row order, as in sequential evaluation.
+        let parts = ranges
+            .into_par_iter()
+            .map(|r| self.run_range::<O>(r))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let parts: Vec<&dyn Array> = parts.iter().map(|a| a.as_ref()).collect();
+        Ok(concat(&parts)?)
+    }
+
+    fn run_range<O: RowSink<S>>(&self, rows: Range<usize>) -> Result<ArrayRef, RegexpExtractError> {
+        if !self.pat_scalar {
+            if let Some(groups) = self.group_patterns(rows.clone()) {
+                return self.run_grouped::<O>(rows, groups);
+            }
         }
-    } else if let Some(i64s) = idx_i64 {
-        i64s.null_count() == 0
-    } else if let Some(i32s) = idx_i32 {
-        i32s.null_count() == 0
-    } else {
-        false
-    };
 
-    let use_no_nulls_fast_path = str_no_nulls && pat_no_nulls && idx_no_nulls;
+        let (strings, patterns) = (self.strings, self.patterns);
+        let (idx_i64, idx_i32) = (self.idx_i64, self.idx_i32);
+        let (idx_is_scalar, idx_scalar) = (self.idx_is_scalar, self.idx_scalar);
+        let (pat_scalar, cfg) = (self.pat_scalar, self.cfg);
+        let candidates = &self.candidates;
+
+        let bytes_hint = estimate_bytes(
+            strings,
+            rows.clone(),
+            idx_i64,
+            idx_i32,
+            idx_is_scalar,
+            idx_scalar,
+        );
+        let mut b = O::with_capacity(rows.len(), bytes_hint, cfg);
+        let mut cache = PatternCache::new(cfg.cache_size).with_shared(cfg.pattern_cache.clone());
+        let mut reader = GroupReader::new();
+
+        if self.use_no_nulls_fast_path {
+            for i in rows.clone() {
+                // idx is guaranteed non-null here
+                let idx = if idx_is_scalar {
+                    idx_scalar
+                } else if let Some(i64s) = idx_i64 {
+                    i64s.value(i)
+                } else if let Some(i32s) = idx_i32 {
+                    i32s.value(i) as i64
+                } else {
+                    return Err(RegexpExtractError::MissingIdxArray);
+                };
+
+                let s = strings.value(i);
+                let pat = patterns.value(if pat_scalar { 0 } else { i });
+                if idx < 0 {
+                    let err = RegexpExtractError::NegativeIndex(idx);
+                    b.append_failure(s, row_failure(cfg, i, pat, err))?;
+                    continue;
+                }
 
-    if use_no_nulls_fast_path {
-        // compile scalar pattern once (or use cache for column patterns)
-        if pat_scalar && compiled_scalar.is_none() {
-            match compile(patterns.value(0)) {
-                Ok(re) => compiled_scalar = Some(re),
-                Err(e) => {
-                    if let InvalidPatternMode::EmptyString = mode {
-                        scalar_pat_invalid = true;
-                    } else {
-                        return Err(e.into());
+                // select regex
+                let re = if let Some(scalar) = &self.scalar {
+                    match scalar.regex() {
+                        Some(re) => re,
+                        None => {
+                            b.append_failure(s, scalar.failure(i, cfg))?;
+                            continue;
+                        }
+                    }
+                } else {
+                    match cache.get_or_compile(pat) {
+                        Ok(re) => re,
+                        Err(e) => {
+                            b.append_failure(s, row_failure(cfg, i, pat, e))?;
+                            continue;
+                        }
                     }
+                };
+
+                if candidates.as_ref().is_some_and(|c| !c[i]) {
+                    b.append_match(s, None);
+                    continue;
                 }
+
+                // match
+                match reader.extract(re, s, idx as usize) {
+                    Ok(span) => b.append_match(s, span),
+                    Err(e) => {
+                        let err = RegexpExtractError::MatchError(e.to_string());
+                        b.append_failure(s, row_failure(cfg, i, pat, err))?;
+                    }
+                }
+            }
+
+            #[cfg(feature = "debug-logging")]
+            {
+                let st = cache.stats();
+                eprintln!(
+                    "regexp_extract: rows={} hits={} misses={} compiled={} hit_rate={:.1}%",
+                    rows.len(),
+                    st.hits,
+                    st.misses,
+                    st.compiled,
+                    if st.hits + st.misses == 0 {
+                        100.0
+                    } else {
+                        100.0 * (st.hits as f64) / ((st.hits + st.misses) as f64)
+                    }
+                );
             }
+
+            return Ok(b.finish_array());
         }
 
-        for i in 0..n {
-            // idx is guaranteed non-null here
-            let idx = if idx_is_scalar {
-                idx_scalar
-            } else if let Some(i64s) = idx_i64 {
-                i64s.value(i)
+        for i in rows {
+            let pat_is_null = if pat_scalar {
+                patterns.is_null(0)
             } else {
-                idx_i32.unwrap().value(i) as i64
+                patterns.is_null(i)
             };
-
-            if idx < 0 {
-                return Err(RegexpExtractError::NegativeIndex(idx));
+            if strings.is_null(i) || pat_is_null {
+                b.append_null();
+                continue;
             }
 
-            // select regex
-            let re = if pat_scalar {
-                if scalar_pat_invalid {
-                    b.append_value("");
-                    continue;
-                }
-                compiled_scalar.as_ref().unwrap()
-            } else {
-                cache.get_or_compile(patterns.value(i))?
+            let s = strings.value(i);
+
+            // a NULL scalar idx nulls every row, like a NULL column value
+            let Some(idx) = self.idx_at(i)? else {
+                b.append_null();
+                continue;
             };
 
-            // match
-            let s = strings.value(i);
-            let out: &str = match captures(re, s) {
-                Ok(Some(caps)) => {
-                    if idx == 0 {
-                        caps.get(0).map(|m| m.as_str()).unwrap_or("")
-                    } else {
-                        caps.get(idx as usize).map(|m| m.as_str()).unwrap_or("")
+            let pat = patterns.value(if pat_scalar { 0 } else { i });
+            if idx < 0 {
+                let err = RegexpExtractError::NegativeIndex(idx);
+                b.append_failure(s, row_failure(cfg, i, pat, err))?;
+                continue;
+            }
+
+            let re: &Regex = if let Some(scalar) = &self.scalar {
+                match scalar.regex() {
+                    Some(re) => re,
+                    None => {
+                        b.append_failure(s, scalar.failure(i, cfg))?;
+                        continue;
                     }
                 }
-                Ok(None) => "",
-                Err(e) => {
-                    if let InvalidPatternMode::EmptyString = mode {
-                        ""
-                    } else {
-                        return Err(RegexpExtractError::MatchError(e.to_string()));
+            } else {
+                match cache.get_or_compile(pat) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        b.append_failure(s, row_failure(cfg, i, pat, e))?;
+                        continue;
                     }
                 }
             };
 
-            b.append_value(out);
-        }
-
-        #[cfg(feature = "debug-logging")]
-        {
-            let st = cache.stats();
-            eprintln!(
-                "regexp_extract: rows={} hits={} misses={} compiled={} hit_rate={:.1}%",
-                n,
-                st.hits,
-                st.misses,
-                st.compiled,
-                if st.hits + st.misses == 0 {
-                    100.0
-                } else {
-                    100.0 * (st.hits as f64) / ((st.hits + st.misses) as f64)
-                }
-            );
-        }
-
-        return Ok(b.finish_array());
-    }
-
-    if pat_scalar && compiled_scalar.is_none() {
-        match compile(patterns.value(0)) {
-            Ok(re) => {
-                compiled_scalar = Some(re);
-                scalar_pat_invalid = false;
+            if candidates.as_ref().is_some_and(|c| !c[i]) {
+                b.append_match(s, None);
+                continue;
             }
-            Err(e) => {
-                if let InvalidPatternMode::EmptyString = mode {
-                    scalar_pat_invalid = true; // emit "" per row below
-                } else {
-                    return Err(e.into());
+
+            match reader.extract(re, s, idx as usize) {
+                Ok(span) => b.append_match(s, span),
+                Err(e) => {
+                    let err = RegexpExtractError::MatchError(e.to_string());
+                    b.append_failure(s, row_failure(cfg, i, pat, err))?;
                 }
             }
         }
+
+        Ok(b.finish_array())
     }
+}
 
-    for i in 0..n {
-        let pat_is_null = if pat_scalar {
-            patterns.is_null(0)
-        } else {
-            patterns.is_null(i)
-        };
-        if strings.is_null(i) || pat_is_null {
-            b.append_null();
-            continue;
+impl<'a, S, P> Batch<'a, S, P>
+where
+    S: StrArray,
+    P: StrArray,
+{
+    /// Buckets the rows of a column pattern by pattern text, or `None` when there are
+    /// too many distinct patterns for grouping to beat the LRU cache. Null patterns are
+    /// left out.
+    fn group_patterns(&self, rows: Range<usize>) -> Option<HashMap<&'a str, Vec<usize>>> {
+        if rows.len() < GROUPED_MIN_ROWS {
+            return None;
         }
+        let max_distinct = rows.len() / GROUPED_DISTINCT_RATIO;
 
-        let s = strings.value(i);
-
-        let idx = if idx_is_scalar {
-            idx_scalar
-        } else if let Some(i64s) = idx_i64 {
-            if i64s.is_null(i) {
-                b.append_null();
+        let mut groups: HashMap<&'a str, Vec<usize>> = HashMap::new();
+        for i in rows {
+            if self.patterns.is_null(i) {
                 continue;
             }
-            i64s.value(i)
-        } else if let Some(i32s) = idx_i32 {
-            if i32s.is_null(i) {
-                b.append_null();
+            let pat = self.patterns.value(i);
+            if let Some(rows) = groups.get_mut(pat) {
+                rows.push(i);
                 continue;
             }
-            i32s.value(i) as i64
-        } else {
-            return Err(RegexpExtractError::MissingIdxArray);
-        };
-
-        if idx < 0 {
-            return Err(RegexpExtractError::NegativeIndex(idx));
+            if groups.len() >= max_distinct {
+                return None;
+            }
+            groups.insert(pat, vec![i]);
         }
+        Some(groups)
+    }
 
-        let re: &Regex = if pat_scalar {
-            if scalar_pat_invalid {
-                b.append_value("");
-                continue;
-            }
-            compiled_scalar.as_ref().expect("compiled scalar regex")
-        } else {
-            match cache.get_or_compile(patterns.value(i)) {
-                Ok(r) => r,
-                Err(e) => {
-                    if let InvalidPatternMode::EmptyString = mode {
-                        b.append_value("");
-                        continue;
-                    } else {
-                        return Err(e);
-                    }
+    /// Column-pattern strategy for few distinct patterns in arbitrary order: each pattern
+    /// is compiled once and its rows matched together, then results are scattered back
+    /// into row order. Errors are reported for the earliest failing row, exactly as the
+    /// row-by-row loops would.
+    fn run_grouped<O: RowSink<S>>(
+        &self,
+        rows: Range<usize>,
+        groups: HashMap<&'a str, Vec<usize>>,
+    ) -> Result<ArrayRef, RegexpExtractError> {
+        let cfg = self.cfg;
+        let mut out: Vec<Slot> = vec![Slot::Null; rows.len()];
+        let mut first_err: Option<(usize, RegexpExtractError)> = None;
+
+        for (pat, group) in groups {
+            let mut compiled: Option<ScalarPattern<Regex>> = None;
+            let mut reader = GroupReader::new();
+
+            for i in group {
+                // an earlier row already failed; nothing later in this group can win
+                if first_err.as_ref().is_some_and(|(row, _)| *row < i) {
+                    break;
                 }
-            }
-        };
-
-        let out: &str = match captures(re, s) {
-            Ok(Some(caps)) => {
-                if idx == 0 {
-                    caps.get(0).map(|m| m.as_str()).unwrap_or("")
-                } else {
-                    let gi = idx as usize;
-                    caps.get(gi).map(|m| m.as_str()).unwrap_or("")
+                if self.strings.is_null(i) {
+                    continue;
                 }
-            }
-            Ok(None) => "",
-            Err(e) => {
-                if let InvalidPatternMode::EmptyString = mode {
-                    ""
+                let idx = match self.idx_at(i) {
+                    Ok(Some(idx)) => idx,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        first_err = Some((i, e));
+                        break;
+                    }
+                };
+
+                let failure = if idx < 0 {
+                    Some(row_failure(
+                        cfg,
+                        i,
+                        pat,
+                        RegexpExtractError::NegativeIndex(idx),
+                    ))
                 } else {
-                    return Err(RegexpExtractError::MatchError(e.to_string()));
+                    // compiled on the group's first evaluated row
+                    let pattern = match compiled {
+                        Some(ref p) => p,
+                        None => {
+                            let shared = cfg.pattern_cache.as_ref();
+                            let p = match Regex::compile_with(shared, pat) {
+                                Ok(re) => ScalarPattern::Compiled(re),
+                                Err(e) => match row_failure(cfg, i, pat, e) {
+                                    RowFailure::NoMatch => ScalarPattern::NoMatch,
+                                    RowFailure::Null(e) => {
+                                        out[i - rows.start] = Slot::Error(e.clone());
+                                        compiled = Some(ScalarPattern::Invalid(e));
+                                        continue;
+                                    }
+                                    RowFailure::Fail(e) => {
+                                        first_err = Some((i, e));
+                                        break;
+                                    }
+                                },
+                            };
+                            &*compiled.insert(p)
+                        }
+                    };
+                    match pattern {
+                        ScalarPattern::Compiled(re) => {
+                            let s = self.strings.value(i);
+                            match reader.extract(re, s, idx as usize) {
+                                Ok(span) => {
+                                    out[i - rows.start] = Slot::Match(span);
+                                    None
+                                }
+                                Err(e) => {
+                                    let err = RegexpExtractError::MatchError(e.to_string());
+                                    Some(row_failure(cfg, i, pat, err))
+                                }
+                            }
+                        }
+                        invalid => Some(invalid.failure(i, cfg)),
+                    }
+                };
+
+                match failure {
+                    None => {}
+                    Some(RowFailure::NoMatch) => out[i - rows.start] = Slot::Match(None),
+                    Some(RowFailure::Null(e)) => out[i - rows.start] = Slot::Error(e),
+                    Some(RowFailure::Fail(e)) => {
+                        first_err = Some((i, e));
+                        break;
+                    }
                 }
             }
-        };
+        }
+
+        if let Some((_, e)) = first_err {
+            return Err(e);
+        }
+
+        let bytes_hint = estimate_bytes(
+            self.strings,
+            rows.clone(),
+            self.idx_i64,
+            self.idx_i32,
+            self.idx_is_scalar,
+            self.idx_scalar,
+        );
+        let mut b = O::with_capacity(rows.len(), bytes_hint, cfg);
+        for (i, v) in rows.zip(out) {
+            match v {
+                Slot::Match(span) => b.append_match(self.strings.value(i), span),
+                Slot::Error(e) => b.append_error(&e),
+                Slot::Null => b.append_null(),
+            }
+        }
+        Ok(b.finish_array())
+    }
+
+    /// `idx` for row `i`; `None` when it is null.
+    #[inline]
+    fn idx_at(&self, i: usize) -> Result<Option<i64>, RegexpExtractError> {
+        if self.idx_is_scalar {
+            let null = match (self.idx_i64, self.idx_i32) {
+                (Some(i64s), _) => i64s.is_null(0),
+                (_, Some(i32s)) => i32s.is_null(0),
+                _ => false,
+            };
+            return Ok((!null).then_some(self.idx_scalar));
+        }
+        match (self.idx_i64, self.idx_i32) {
+            (Some(i64s), _) => Ok((!i64s.is_null(i)).then(|| i64s.value(i))),
+            (_, Some(i32s)) => Ok((!i32s.is_null(i)).then(|| i32s.value(i) as i64)),
+            _ => Err(RegexpExtractError::MissingIdxArray),
+        }
+    }
+}
 
-        b.append_value(out);
+/// Extracts groups while reusing one capture buffer across rows.
+///
+/// A buffer only depends on how many groups the regex has (these are single-pattern
+/// regexes, so the slot layout follows from the count), so it is kept across pattern
+/// changes and rebuilt only when the group count differs.
+pub(crate) struct GroupReader {
+    buf: Option<(usize, CaptureBuf)>,
+}
+
+impl GroupReader {
+    pub(crate) fn new() -> Self {
+        Self { buf: None }
     }
 
-    Ok(b.finish_array())
+    /// Capture buffer for `re`, reused when the previous regex had as many groups.
+    #[inline]
+    pub(crate) fn buf_for(&mut self, re: &Regex) -> &mut CaptureBuf {
+        let groups = re.captures_len();
+        if self.buf.as_ref().is_some_and(|(n, _)| *n != groups) {
+            self.buf = None;
+        }
+        &mut self.buf.get_or_insert_with(|| (groups, capture_buf(re))).1
+    }
+
+    /// Byte span of group `idx` in the first match in `s`; `None` when nothing matched or
+    /// the group did not participate. `idx = 0` only needs the match span, so it skips captures.
+    #[inline]
+    fn extract(
+        &mut self,
+        re: &Regex,
+        s: &str,
+        idx: usize,
+    ) -> Result<Option<(usize, usize)>, Box<RegexError>> {
+        if idx == 0 {
+            return find_at(re, s, 0);
+        }
+        let buf = self.buf_for(re);
+        if !captures_read(re, buf, s)? {
+            return Ok(None);
+        }
+        Ok(buf.get(idx))
+    }
+}
+
+/// Every `(argument, len)` after the string column must have one value per row, or a
+/// single value used for every row.
+pub(crate) fn check_lengths(
+    rows: usize,
+    args: &[(&'static str, usize)],
+) -> Result<(), RegexpExtractError> {
+    for &(argument, len) in args {
+        if len != rows && len != 1 {
+            return Err(RegexpExtractError::LengthMismatch {
+                argument,
+                rows,
+                len,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Rows that may match a compiled scalar pattern, per its required-literal prefilter.
+/// `None` means every row must be matched.
+pub(crate) fn scalar_candidates<S, P>(
+    strings: &S,
+    patterns: &P,
+    compiled: bool,
+) -> Option<Vec<bool>>
+where
+    S: StrArray,
+    P: StrArray,
+{
+    if patterns.len() != 1 || !compiled {
+        return None;
+    }
+    Prefilter::new(patterns.value(0)).map(|pf| strings.candidates(&pf))
 }
 
 // -------------------- Public wrappers (stable API) --------------------
@@ -334,7 +869,10 @@ pub fn run_utf8_utf8(
     cache_cap: usize,
     mode: InvalidPatternMode,
 ) -> Result<ArrayRef, RegexpExtractError> {
-    run_generic(strings, patterns, idx_i64, idx_i32, cache_cap, mode)
+    let cfg = RegexpExtractConfig::new()
+        .cache_size(cache_cap)
+        .invalid_pattern_mode(mode);
+    run_generic(strings, patterns, idx_i64, idx_i32, &cfg)
 }
 
 /// LargeUtf8 strings with Utf8 patterns
@@ -347,7 +885,10 @@ pub fn run_large_utf8_utf8(
     cache_cap: usize,
     mode: InvalidPatternMode,
 ) -> Result<ArrayRef, RegexpExtractError> {
-    run_generic(strings, patterns, idx_i64, idx_i32, cache_cap, mode)
+    let cfg = RegexpExtractConfig::new()
+        .cache_size(cache_cap)
+        .invalid_pattern_mode(mode);
+    run_generic(strings, patterns, idx_i64, idx_i32, &cfg)
 }
 
 /// Utf8 strings with LargeUtf8 patterns
@@ -360,7 +901,10 @@ pub fn run_utf8_largeutf8(
     cache_cap: usize,
     mode: InvalidPatternMode,
 ) -> Result<ArrayRef, RegexpExtractError> {
-    run_generic(strings, patterns, idx_i64, idx_i32, cache_cap, mode)
+    let cfg = RegexpExtractConfig::new()
+        .cache_size(cache_cap)
+        .invalid_pattern_mode(mode);
+    run_generic(strings, patterns, idx_i64, idx_i32, &cfg)
 }
 
 /// LargeUtf8 strings with LargeUtf8 patterns
@@ -373,24 +917,26 @@ pub fn run_large_utf8_largeutf8(
     cache_cap: usize,
     mode: InvalidPatternMode,
 ) -> Result<ArrayRef, RegexpExtractError> {
-    run_generic(strings, patterns, idx_i64, idx_i32, cache_cap, mode)
+    let cfg = RegexpExtractConfig::new()
+        .cache_size(cache_cap)
+        .invalid_pattern_mode(mode);
+    run_generic(strings, patterns, idx_i64, idx_i32, &cfg)
 }
 
 #[inline]
 fn estimate_bytes<S: StrArray>(
     strings: &S,
+    rows: Range<usize>,
     idx_i64: Option<&Int64Array>,
     idx_i32: Option<&Int32Array>,
     idx_is_scalar: bool,
     idx_scalar: i64,
 ) -> usize {
-    let n = strings.len();
-
     // Base lower bound (what we used before).
-    let mut bytes = n.saturating_mul(4);
+    let mut bytes = rows.len().saturating_mul(4);
 
     let mut sum_input = 0usize;
-    for i in 0..n {
+    for i in rows {
         if strings.is_null(i) {
             continue;
         }
@@ -1,17 +1,43 @@
 //! datafusion-regexp-extract-udf
 
 // Public API surface (will export the UDF factory once implmented)
+pub mod binary;
+pub mod classify;
+pub mod codec;
 mod config;
 pub mod error;
 pub mod eval;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod kernel;
+pub mod lint;
 pub mod pattern_cache;
+pub mod prefilter;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod re;
+pub mod rlike;
+pub mod search;
+pub mod session;
+pub mod span;
+pub mod split;
+pub mod substrait;
 pub mod types;
 pub mod udf;
 
-pub use config::{InvalidPatternMode, RegexpExtractConfig};
-pub use udf::{regexp_extract_udf, regexp_extract_udf_with};
+pub use classify::{regexp_classify_udf, regexp_match_any_udf};
+pub use config::{BinaryOutput, InvalidPatternMode, RegexpExtractConfig};
+pub use error::{ErrorLog, RowError, RowErrorKind};
+pub use pattern_cache::{SharedPatternCache, WarmResult};
+pub use rlike::{rlike_udf, rlike_udf_with};
+pub use search::{
+    regexp_count_udf, regexp_count_udf_with, regexp_instr_udf, regexp_instr_udf_with,
+    regexp_substr_udf, regexp_substr_udf_with,
+};
+pub use session::{RegexpExtractOptions, enable_session_options};
+pub use span::{regexp_extract_span_udf, regexp_extract_span_udf_with};
+pub use split::{split_udf, split_udf_with};
+pub use udf::{regexp_extract_config, regexp_extract_udf, regexp_extract_udf_with};
 
 /// Returns a ping message; used by the smoke test
 pub fn ping() -> &'static str {
@@ -0,0 +1,641 @@
+//! Pattern linter: which engines accept a pattern, and where it may disagree with Spark.
+//!
+//! [`lint_pattern`] compiles the pattern with every engine built into this crate and scans
+//! its text for constructs that need `fancy-regex` (look-around, backreferences, atomic
+//! groups, possessive quantifiers), Java-only syntax, syntax Rust and Java read differently,
+//! and nested unbounded quantifiers. The scan is syntactic, so it also reports on patterns
+//! that no engine accepts. Spans are byte ranges in the pattern.
+
+use crate::re::BYTES_ENGINE;
+use std::fmt::Write as _;
+use std::ops::Range;
+
+/// What a [`Finding`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FindingKind {
+    /// `(?=..)`, `(?!..)`, `(?<=..)`, `(?<!..)`.
+    LookAround,
+    /// `\1`, `\k<name>`.
+    Backreference,
+    /// `(?>..)`.
+    AtomicGroup,
+    /// `a*+`, `a++`, `a?+`, `a{n,m}+`.
+    PossessiveQuantifier,
+    /// Escapes Java and `fancy-regex` accept but `regex` rejects: `\e`, `\G`, `\Z`.
+    BacktrackingEscape,
+    /// Java syntax no engine here accepts, e.g. `\Q..\E` or `\p{javaLowerCase}`.
+    JavaOnly,
+    /// Accepted here but matched differently than in Java, e.g. `\d` is Unicode-aware.
+    JavaDifference,
+    /// An unbounded quantifier over a group that itself contains one, e.g. `(a+)+`:
+    /// exponential time on backtracking engines (Java, `fancy-regex`).
+    CatastrophicBacktracking,
+}
+
+impl FindingKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LookAround => "look_around",
+            Self::Backreference => "backreference",
+            Self::AtomicGroup => "atomic_group",
+            Self::PossessiveQuantifier => "possessive_quantifier",
+            Self::BacktrackingEscape => "backtracking_escape",
+            Self::JavaOnly => "java_only",
+            Self::JavaDifference => "java_difference",
+            Self::CatastrophicBacktracking => "catastrophic_backtracking",
+        }
+    }
+
+    /// Whether the construct compiles with `fancy-regex` but not with `regex`.
+    pub fn needs_fancy_regex(self) -> bool {
+        matches!(
+            self,
+            Self::LookAround
+                | Self::Backreference
+                | Self::AtomicGroup
+                | Self::PossessiveQuantifier
+                | Self::BacktrackingEscape
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// Byte range in the pattern.
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+/// Compile result of one engine; `error` is `None` when the pattern compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineCheck {
+    pub engine: &'static str,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub pattern: String,
+    /// One entry per engine built into this crate: `regex`, `regex::bytes` (binary input)
+    /// and, with the `fancy-regex` feature, `fancy-regex`.
+    pub engines: Vec<EngineCheck>,
+    /// Number of capture groups, i.e. the largest valid `idx`.
+    pub capture_groups: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Whether the engine `regexp_extract` uses for string input accepts the pattern.
+    pub fn compiles(&self) -> bool {
+        self.engines
+            .iter()
+            .find(|e| e.engine == crate::re::ENGINE)
+            .is_some_and(|e| e.error.is_none())
+    }
+
+    /// Whether the pattern uses constructs only `fancy-regex` supports.
+    pub fn needs_fancy_regex(&self) -> bool {
+        self.findings.iter().any(|f| f.kind.needs_fancy_regex())
+    }
+
+    /// The report as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"pattern\":");
+        json_str(&mut out, &self.pattern);
+        let _ = write!(
+            out,
+            ",\"compiles\":{},\"needs_fancy_regex\":{},\"capture_groups\":{},\"engines\":[",
+            self.compiles(),
+            self.needs_fancy_regex(),
+            self.capture_groups
+        );
+        for (i, e) in self.engines.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{{\"engine\":\"{}\",\"error\":", e.engine);
+            match &e.error {
+                Some(msg) => json_str(&mut out, msg),
+                None => out.push_str("null"),
+            }
+            out.push('}');
+        }
+        out.push_str("],\"findings\":[");
+        for (i, f) in self.findings.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(
+                out,
+                "{{\"kind\":\"{}\",\"start\":{},\"end\":{},\"message\":",
+                f.kind.as_str(),
+                f.span.start,
+                f.span.end
+            );
+            json_str(&mut out, &f.message);
+            out.push('}');
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+fn json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Lints `pat`; see the [module docs](self).
+pub fn lint_pattern(pat: &str) -> Report {
+    let engines = vec![
+        EngineCheck {
+            engine: "regex",
+            error: regex::Regex::new(pat).err().map(|e| e.to_string()),
+        },
+        EngineCheck {
+            engine: BYTES_ENGINE,
+            error: regex::bytes::Regex::new(pat).err().map(|e| e.to_string()),
+        },
+        #[cfg(feature = "fancy-regex")]
+        EngineCheck {
+            engine: "fancy-regex",
+            error: fancy_regex::Regex::new(pat).err().map(|e| e.to_string()),
+        },
+    ];
+
+    let mut scan = Scan {
+        pat,
+        findings: Vec::new(),
+        groups: 0,
+        shorthand_seen: Vec::new(),
+    };
+    scan.run();
+    Report {
+        pattern: pat.to_string(),
+        engines,
+        capture_groups: scan.groups,
+        findings: scan.findings,
+    }
+}
+
+/// Java's POSIX classes: ASCII-only there, Unicode properties (or unknown) here.
+const POSIX_CLASSES: &[&str] = &[
+    "Lower", "Upper", "ASCII", "Alpha", "Digit", "Alnum", "Punct", "Graph", "Print", "Blank",
+    "Cntrl", "XDigit", "Space",
+];
+
+/// Open group while scanning: where it starts and whether it holds an unbounded quantifier.
+struct Frame {
+    start: usize,
+    unbounded: bool,
+}
+
+struct Scan<'p> {
+    pat: &'p str,
+    findings: Vec<Finding>,
+    groups: usize,
+    /// `\d`-style classes already reported; each is reported once per pattern.
+    shorthand_seen: Vec<u8>,
+}
+
+impl Scan<'_> {
+    fn push(&mut self, kind: FindingKind, span: Range<usize>, message: impl Into<String>) {
+        self.findings.push(Finding {
+            kind,
+            span,
+            message: message.into(),
+        });
+    }
+
+    fn run(&mut self) {
+        let b = self.pat.as_bytes();
+        let mut stack = vec![Frame {
+            start: 0,
+            unbounded: false,
+        }];
+        let mut class_depth = 0usize;
+        let mut i = 0;
+        while i < b.len() {
+            match b[i] {
+                b'\\' => {
+                    i = self.escape(i, class_depth > 0);
+                }
+                b'[' if class_depth > 0 && b.get(i + 1) == Some(&b':') => {
+                    // `[:alpha:]` inside a class
+                    i = self.pat[i..].find(":]").map_or(b.len(), |p| i + p + 2);
+                }
+                b'[' => {
+                    class_depth += 1;
+                    i += 1;
+                    // a leading `^` and then `]` are literal
+                    if b.get(i) == Some(&b'^') {
+                        i += 1;
+                    }
+                    if b.get(i) == Some(&b']') {
+                        i += 1;
+                    }
+                }
+                b']' if class_depth > 0 => {
+                    class_depth -= 1;
+                    i += 1;
+                }
+                _ if class_depth > 0 => i += 1,
+                b'(' => i = self.open_group(i, &mut stack),
+                b')' => {
+                    if stack.len() > 1 {
+                        let frame = stack.pop().expect("open group");
+                        if let Some((end, true)) = quantifier_at(b, i + 1) {
+                            if frame.unbounded {
+                                self.push(
+                                    FindingKind::CatastrophicBacktracking,
+                                    frame.start..end,
+                                    "nested unbounded quantifiers can backtrack exponentially",
+                                );
+                            }
+                        }
+                        stack.last_mut().expect("outer frame").unbounded |= frame.unbounded;
+                    }
+                    i += 1;
+                }
+                b'$' => {
+                    self.push(
+                        FindingKind::JavaDifference,
+                        i..i + 1,
+                        "in Java `$` also matches before a final line terminator",
+                    );
+                    i += 1;
+                }
+                _ => match quantifier_at(b, i) {
+                    Some((mut end, unbounded)) => {
+                        stack.last_mut().expect("outer frame").unbounded |= unbounded;
+                        match b.get(end) {
+                            Some(b'+') => {
+                                self.push(
+                                    FindingKind::PossessiveQuantifier,
+                                    i..end + 1,
+                                    "possessive quantifiers need fancy-regex",
+                                );
+                                end += 1;
+                            }
+                            Some(b'?') => end += 1,
+                            _ => {}
+                        }
+                        i = end;
+                    }
+                    None => i += 1,
+                },
+            }
+        }
+    }
+
+    /// Handles the `(` at `i`; returns the index to continue from.
+    fn open_group(&mut self, i: usize, stack: &mut Vec<Frame>) -> usize {
+        let rest = &self.pat[i + 1..];
+        let (len, capturing) = if rest.starts_with("?<=") || rest.starts_with("?<!") {
+            self.push(
+                FindingKind::LookAround,
+                i..i + 4,
+                "look-behind needs fancy-regex",
+            );
+            (4, false)
+        } else if rest.starts_with("?=") || rest.starts_with("?!") {
+            self.push(
+                FindingKind::LookAround,
+                i..i + 3,
+                "look-ahead needs fancy-regex",
+            );
+            (3, false)
+        } else if rest.starts_with("?>") {
+            self.push(
+                FindingKind::AtomicGroup,
+                i..i + 3,
+                "atomic groups need fancy-regex",
+            );
+            (3, false)
+        } else if rest.starts_with("?P<") {
+            self.push(
+                FindingKind::JavaDifference,
+                i..i + 4,
+                "`(?P<name>..)` is not Java syntax; Spark only accepts `(?<name>..)`",
+            );
+            (4, true)
+        } else if rest.starts_with("?<") {
+            (3, true)
+        } else if rest.starts_with("?#") {
+            // comment group: skip it whole
+            return self.pat[i..]
+                .find(')')
+                .map_or(self.pat.len(), |p| i + p + 1);
+        } else if let Some(flags) = rest.strip_prefix('?') {
+            let n = flags
+                .bytes()
+                .take_while(|c| c.is_ascii_alphabetic() || *c == b'-')
+                .count();
+            self.flags(i + 2, &flags[..n]);
+            match flags.as_bytes().get(n) {
+                // `(?i)`: flags for the rest of the enclosing group, not a group itself
+                Some(b')') => return i + 2 + n + 1,
+                _ => (2 + n + 1, false),
+            }
+        } else {
+            (1, true)
+        };
+        if capturing {
+            self.groups += 1;
+        }
+        stack.push(Frame {
+            start: i,
+            unbounded: false,
+        });
+        i + len
+    }
+
+    /// Inline flags starting at byte `at`.
+    fn flags(&mut self, at: usize, flags: &str) {
+        for (k, c) in flags.char_indices() {
+            let span = at + k..at + k + 1;
+            match c {
+                'U' => self.push(
+                    FindingKind::JavaDifference,
+                    span,
+                    "`U` makes quantifiers lazy here; in Java it enables Unicode classes",
+                ),
+                'd' => self.push(FindingKind::JavaOnly, span, "`d` (UNIX_LINES) is Java-only"),
+                _ => {}
+            }
+        }
+    }
+
+    /// Handles the escape at `i`; returns the index after it.
+    fn escape(&mut self, i: usize, in_class: bool) -> usize {
+        let b = self.pat.as_bytes();
+        let Some(c) = self.pat[i + 1..].chars().next() else {
+            return i + 1;
+        };
+        let end = i + 1 + c.len_utf8();
+        match c {
+            '1'..='9' if !in_class => {
+                let end = end + b[end..].iter().take_while(|d| d.is_ascii_digit()).count();
+                self.push(
+                    FindingKind::Backreference,
+                    i..end,
+                    "backreferences need fancy-regex",
+                );
+                end
+            }
+            'k' if b.get(end) == Some(&b'<') => {
+                let end = self.pat[end..].find('>').map_or(b.len(), |p| end + p + 1);
+                self.push(
+                    FindingKind::Backreference,
+                    i..end,
+                    "backreferences need fancy-regex",
+                );
+                end
+            }
+            'Q' => {
+                let end = self.pat[end..].find("\\E").map_or(b.len(), |p| end + p + 2);
+                self.push(
+                    FindingKind::JavaOnly,
+                    i..end,
+                    "`\\Q..\\E` quoting is Java-only; escape the characters instead",
+                );
+                end
+            }
+            'e' | 'G' | 'Z' => {
+                self.push(
+                    FindingKind::BacktrackingEscape,
+                    i..end,
+                    format!("`\\{c}` needs fancy-regex"),
+                );
+                end
+            }
+            'R' | 'X' | 'c' | '0' => {
+                // `\cX` and `\0nn` carry their argument
+                let end = match c {
+                    'c' => end + self.pat[end..].chars().next().map_or(0, char::len_utf8),
+                    '0' => {
+                        end + b[end..]
+                            .iter()
+                            .take(3)
+                            .take_while(|d| d.is_ascii_digit())
+                            .count()
+                    }
+                    _ => end,
+                };
+                self.push(
+                    FindingKind::JavaOnly,
+                    i..end,
+                    format!("`\\{c}` is Java-only"),
+                );
+                end
+            }
+            'h' | 'H' => {
+                self.push(
+                    FindingKind::JavaDifference,
+                    i..end,
+                    format!("`\\{c}` is horizontal whitespace in Java, a hex digit in fancy-regex"),
+                );
+                end
+            }
+            'v' => {
+                self.push(
+                    FindingKind::JavaDifference,
+                    i..end,
+                    "`\\v` is a vertical tab here, any vertical whitespace in Java",
+                );
+                end
+            }
+            'd' | 'D' | 'w' | 'W' | 's' | 'S' | 'b' | 'B' => {
+                if !self.shorthand_seen.contains(&(c as u8)) {
+                    self.shorthand_seen.push(c as u8);
+                    self.push(
+                        FindingKind::JavaDifference,
+                        i..end,
+                        format!("`\\{c}` is Unicode-aware here, ASCII-only in Java; use `(?-u:\\{c})` to match Spark"),
+                    );
+                }
+                end
+            }
+            'p' | 'P' => self.property(i, end),
+            _ => end,
+        }
+    }
+
+    /// `\p{Name}` / `\pL` whose escape letter ends at `end`; returns the index after it.
+    fn property(&mut self, i: usize, end: usize) -> usize {
+        let (name, end) = match self.pat[end..].strip_prefix('{') {
+            Some(rest) => match rest.find('}') {
+                Some(p) => (&rest[..p], end + 1 + p + 1),
+                None => return self.pat.len(),
+            },
+            None => {
+                let len = self.pat[end..].chars().next().map_or(0, char::len_utf8);
+                (&self.pat[end..end + len], end + len)
+            }
+        };
+        if name.starts_with("java") {
+            self.push(
+                FindingKind::JavaOnly,
+                i..end,
+                format!("`{name}` is a Java-only class"),
+            );
+        } else if name.starts_with("In") && name.len() > 2 {
+            self.push(
+                FindingKind::JavaOnly,
+                i..end,
+                format!("Unicode block `{name}` is Java-only"),
+            );
+        } else if POSIX_CLASSES.contains(&name) {
+            self.push(
+                FindingKind::JavaDifference,
+                i..end,
+                format!(
+                    "`{name}` is an ASCII POSIX class in Java, a Unicode property (or unknown) here"
+                ),
+            );
+        }
+        end
+    }
+}
+
+/// Quantifier starting at `i`: its end and whether it is unbounded. A `{` that does not
+/// start `{n}`, `{n,}` or `{n,m}` is a literal.
+fn quantifier_at(b: &[u8], i: usize) -> Option<(usize, bool)> {
+    match b.get(i)? {
+        b'*' | b'+' => Some((i + 1, true)),
+        b'?' => Some((i + 1, false)),
+        b'{' => {
+            let digits = |from: usize| b[from..].iter().take_while(|d| d.is_ascii_digit()).count();
+            let lo = digits(i + 1);
+            if lo == 0 {
+                return None;
+            }
+            let mut j = i + 1 + lo;
+            let mut unbounded = false;
+            if b.get(j) == Some(&b',') {
+                let hi = digits(j + 1);
+                unbounded = hi == 0;
+                j += 1 + hi;
+            }
+            (b.get(j) == Some(&b'}')).then_some((j + 1, unbounded))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(pat: &str) -> Vec<FindingKind> {
+        lint_pattern(pat).findings.iter().map(|f| f.kind).collect()
+    }
+
+    #[test]
+    fn plain_pattern_is_clean() {
+        let r = lint_pattern(r"id=([a-z]+)-([0-9]{2,4})");
+        assert!(r.compiles());
+        assert!(!r.needs_fancy_regex());
+        assert_eq!(r.capture_groups, 2);
+        assert!(r.findings.is_empty());
+        assert!(r.engines.iter().all(|e| e.error.is_none()));
+    }
+
+    #[test]
+    fn fancy_only_constructs() {
+        let r = lint_pattern(r"(?<=id=)(\w)\1(?>ab|a)x++");
+        assert!(r.needs_fancy_regex());
+        assert_eq!(r.engines[0].engine, "regex");
+        assert!(r.engines[0].error.is_some());
+        assert_eq!(
+            kinds(r"(?<=id=)(\w)\1(?>ab|a)x++"),
+            vec![
+                FindingKind::LookAround,
+                FindingKind::JavaDifference,
+                FindingKind::Backreference,
+                FindingKind::AtomicGroup,
+                FindingKind::PossessiveQuantifier,
+            ]
+        );
+        assert_eq!(r.findings[0].span, 0..4);
+        assert_eq!(r.findings[2].span, 12..14);
+        assert_eq!(r.capture_groups, 1);
+    }
+
+    #[test]
+    fn java_only_and_differences() {
+        assert_eq!(
+            kinds(r"\Qa.b\E\p{javaLowerCase}\p{InGreek}(?d)x"),
+            vec![
+                FindingKind::JavaOnly,
+                FindingKind::JavaOnly,
+                FindingKind::JavaOnly,
+                FindingKind::JavaOnly
+            ]
+        );
+        assert_eq!(
+            kinds(r"\p{Alpha}\v$(?U)a+"),
+            vec![
+                FindingKind::JavaDifference,
+                FindingKind::JavaDifference,
+                FindingKind::JavaDifference,
+                FindingKind::JavaDifference
+            ]
+        );
+        // shorthand classes are reported once each
+        assert_eq!(kinds(r"\d+\d+\w").len(), 2);
+    }
+
+    #[test]
+    fn escapes_and_classes_are_not_syntax() {
+        assert!(kinds(r"\(?<=\)\$[$(?=]\\1").is_empty());
+        assert_eq!(lint_pattern(r"\((a)[(]").capture_groups, 1);
+    }
+
+    #[test]
+    fn nested_unbounded_quantifiers() {
+        let r = lint_pattern(r"^(a+)+$");
+        assert_eq!(r.findings[0].kind, FindingKind::CatastrophicBacktracking);
+        assert_eq!(r.findings[0].span, 1..6);
+        assert!(
+            kinds(r"(?:x(?:ab*)*)*")
+                .iter()
+                .all(|k| *k == FindingKind::CatastrophicBacktracking)
+        );
+        assert!(kinds(r"(a+)?(ab){1,3}").is_empty());
+        assert!(kinds(r"(a{2,})+").contains(&FindingKind::CatastrophicBacktracking));
+    }
+
+    #[test]
+    fn json_output() {
+        let json = lint_pattern("a\"(").to_json();
+        assert!(json.starts_with(r#"{"pattern":"a\"(","compiles":false,"#));
+        assert!(json.contains(r#""engine":"regex","error":"regex parse error:\n"#));
+        assert!(json.ends_with(r#""findings":[]}"#));
+        assert!(!json.contains('\n'));
+    }
+
+    #[test]
+    fn json_escapes_control_and_keeps_non_ascii() {
+        let pat = "\u{1}\t\u{1f}\u{7f}é東😀\\\"\r\n";
+        let mut out = String::new();
+        json_str(&mut out, pat);
+        assert_eq!(out, "\"\\u0001\\t\\u001f\u{7f}é東😀\\\\\\\"\\r\\n\"");
+        let parsed: serde_json::Value = serde_json::from_str(&lint_pattern(pat).to_json()).unwrap();
+        assert_eq!(parsed["pattern"], pat);
+    }
+}
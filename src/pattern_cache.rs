@@ -1,30 +1,91 @@
 use crate::error::RegexpExtractError;
-use crate::re::{Regex, compile};
+use crate::re::{BytesRegex, Regex, compile, compile_bytes, pattern_error};
 use lru::LruCache;
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct CacheStats {
     pub hits: u64,
     pub misses: u64,
     pub compiled: u64,
+    /// Misses served by the [`SharedPatternCache`] instead of compiling.
+    pub shared_hits: u64,
 }
 
-pub struct PatternCache {
-    lru: LruCache<String, Regex>,
+/// A compiled form of a pattern that [`PatternCache`] can hold.
+pub trait CompiledPattern: Sized {
+    fn compile(pat: &str) -> Result<Self, RegexpExtractError>;
+
+    /// The copy of `pat` precompiled in `shared`, if it holds this pattern type.
+    fn from_shared(_shared: &SharedPatternCache, _pat: &str) -> Option<Self> {
+        None
+    }
+
+    /// [`from_shared`](Self::from_shared) when there is a shared cache, else [`compile`](Self::compile).
+    fn compile_with(
+        shared: Option<&SharedPatternCache>,
+        pat: &str,
+    ) -> Result<Self, RegexpExtractError> {
+        match shared.and_then(|c| Self::from_shared(c, pat)) {
+            Some(re) => Ok(re),
+            None => Self::compile(pat),
+        }
+    }
+}
+
+impl CompiledPattern for Regex {
+    fn compile(pat: &str) -> Result<Self, RegexpExtractError> {
+        compile(pat).map_err(|e| pattern_error(pat, e))
+    }
+
+    fn from_shared(shared: &SharedPatternCache, pat: &str) -> Option<Self> {
+        shared.get(pat)
+    }
+}
+
+impl CompiledPattern for BytesRegex {
+    fn compile(pat: &str) -> Result<Self, RegexpExtractError> {
+        compile_bytes(pat)
+    }
+
+    fn from_shared(shared: &SharedPatternCache, pat: &str) -> Option<Self> {
+        shared.get_bytes(pat)
+    }
+}
+
+pub struct PatternCache<R = Regex> {
+    lru: LruCache<String, R>,
     stats: CacheStats,
+    shared: Option<SharedPatternCache>,
 }
 
 impl PatternCache {
     pub fn new(cap: usize) -> Self {
+        Self::with_capacity(cap)
+    }
+}
+
+impl<R: CompiledPattern> PatternCache<R> {
+    /// Cache for any compiled pattern type, e.g. `PatternCache::<BytesRegex>::with_capacity`.
+    pub fn with_capacity(cap: usize) -> Self {
         // LruCache requires NonZeroUsize; clamp 0 to 1
-        let cap_nz = NonZeroUsize::new(cap.max(1)).unwrap();
+        let cap_nz = NonZeroUsize::new(cap).unwrap_or(NonZeroUsize::MIN);
         Self {
             lru: LruCache::new(cap_nz),
             stats: CacheStats::default(),
+            shared: None,
         }
     }
 
+    /// Misses are looked up in `shared` before compiling.
+    pub fn with_shared(mut self, shared: Option<SharedPatternCache>) -> Self {
+        self.shared = shared;
+        self
+    }
+
     pub fn stats(&self) -> CacheStats {
         self.stats
     }
@@ -33,15 +94,123 @@ impl PatternCache {
         self.stats = CacheStats::default();
     }
 
-    pub fn get_or_compile(&mut self, pat: &str) -> Result<&Regex, RegexpExtractError> {
-        if self.lru.contains(pat) {
-            self.stats.hits += 1;
-            return Ok(self.lru.get(pat).unwrap());
+    pub fn get_or_compile(&mut self, pat: &str) -> Result<&R, RegexpExtractError> {
+        let stats = &mut self.stats;
+        let shared = self.shared.as_ref();
+        let mut missed = false;
+        let re = self
+            .lru
+            .try_get_or_insert_ref(pat, || -> Result<R, RegexpExtractError> {
+                missed = true;
+                stats.misses += 1;
+                if let Some(re) = shared.and_then(|c| R::from_shared(c, pat)) {
+                    stats.shared_hits += 1;
+                    return Ok(re);
+                }
+                let re = R::compile(pat)?;
+                stats.compiled += 1;
+                Ok(re)
+            })?;
+        if !missed {
+            stats.hits += 1;
         }
-        self.stats.misses += 1;
-        let re = compile(pat)?;
-        self.stats.compiled += 1;
-        self.lru.put(pat.to_string(), re);
-        Ok(self.lru.get(pat).expect("entry just inserted"))
+        Ok(re)
+    }
+}
+
+/// Patterns compiled ahead of the first query, shared by every batch and clone.
+///
+/// Set it with `RegexpExtractConfig::pattern_cache` and [`warm`](Self::warm) it at startup:
+/// patterns found here are not compiled again, whatever the per-batch cache size. Each one
+/// is kept compiled for text and, when `regex::bytes` accepts it, for binary input.
+#[derive(Debug, Clone, Default)]
+pub struct SharedPatternCache(Arc<RwLock<HashMap<String, Warmed>>>);
+
+#[derive(Debug)]
+struct Warmed {
+    text: Regex,
+    bytes: Option<BytesRegex>,
+}
+
+/// Outcome of precompiling one pattern with [`SharedPatternCache::warm`].
+#[derive(Debug)]
+pub struct WarmResult {
+    pub pattern: String,
+    pub result: Result<(), RegexpExtractError>,
+}
+
+impl SharedPatternCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compiles each pattern and keeps the valid ones; one result per input, in order.
+    pub fn warm<S: AsRef<str>>(&self, patterns: &[S]) -> Vec<WarmResult> {
+        patterns
+            .iter()
+            .map(|pat| {
+                let pat = pat.as_ref();
+                let result = <Regex as CompiledPattern>::compile(pat).map(|text| {
+                    let bytes = compile_bytes(pat).ok();
+                    self.write().insert(pat.to_string(), Warmed { text, bytes });
+                });
+                WarmResult {
+                    pattern: pat.to_string(),
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    /// [`warm`](Self::warm) with the patterns in a file, one per line. A file containing a
+    /// NUL byte is split on NULs instead (e.g. `find -print0` style output), so patterns
+    /// may span lines, like verbose `(?x)` patterns. Empty entries are skipped; nothing else
+    /// is trimmed, since spaces can be part of a pattern.
+    pub fn warm_from_file(&self, path: impl AsRef<Path>) -> std::io::Result<Vec<WarmResult>> {
+        let text = std::fs::read_to_string(path)?;
+        let patterns = if text.contains('\0') {
+            text.split('\0')
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
+        } else {
+            text.lines().filter(|p| !p.is_empty()).collect()
+        };
+        Ok(self.warm(&patterns))
+    }
+
+    pub fn get(&self, pat: &str) -> Option<Regex> {
+        self.read().get(pat).map(|w| w.text.clone())
+    }
+
+    /// The `regex::bytes` form of a warmed pattern, used for binary input.
+    pub fn get_bytes(&self, pat: &str) -> Option<BytesRegex> {
+        self.read().get(pat).and_then(|w| w.bytes.clone())
+    }
+
+    /// The warmed patterns, sorted.
+    pub fn patterns(&self) -> Vec<String> {
+        let mut patterns = self.read().keys().cloned().collect::<Vec<_>>();
+        patterns.sort();
+        patterns
+    }
+
+    pub fn contains(&self, pat: &str) -> bool {
+        self.read().contains_key(pat)
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, Warmed>> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, Warmed>> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
     }
 }
@@ -0,0 +1,162 @@
+//! Literal prefilter for scalar patterns.
+//!
+//! Most patterns contain a literal that every match must include (e.g. `user_id=` in
+//! `user_id=(\d+)`). We extract that literal (or a small set of alternatives) from the
+//! parsed pattern and scan the whole string buffer once with `memchr`/Aho-Corasick,
+//! so rows that cannot match skip the regex entirely.
+
+use aho_corasick::AhoCorasick;
+use datafusion::arrow::array::OffsetSizeTrait;
+use memchr::memmem;
+use regex_syntax::hir::{Hir, HirKind};
+
+/// Upper bound on alternatives searched with Aho-Corasick; larger sets are not worth it.
+const MAX_ALTERNATIVES: usize = 64;
+
+enum Searcher {
+    One(Box<memmem::Finder<'static>>),
+    Many(AhoCorasick),
+}
+
+pub struct Prefilter {
+    searcher: Searcher,
+}
+
+impl Prefilter {
+    /// Builds a prefilter from the required literals of `pat`.
+    ///
+    /// Returns `None` when the pattern has no required literal or cannot be parsed by
+    /// `regex-syntax` (e.g. look-around under `fancy-regex`); such patterns are not filtered.
+    pub fn new(pat: &str) -> Option<Self> {
+        let hir = regex_syntax::parse(pat).ok()?;
+        let mut lits = required_literals(&hir)?;
+        lits.sort();
+        lits.dedup();
+
+        let searcher = match lits.as_slice() {
+            [one] => Searcher::One(Box::new(memmem::Finder::new(one).into_owned())),
+            many => Searcher::Many(AhoCorasick::new(many).ok()?),
+        };
+        Some(Self { searcher })
+    }
+
+    /// Byte span of the first required literal occurrence in `hay`.
+    #[inline]
+    fn find(&self, hay: &[u8]) -> Option<(usize, usize)> {
+        match &self.searcher {
+            Searcher::One(f) => f.find(hay).map(|s| (s, s + f.needle().len())),
+            Searcher::Many(ac) => ac.find(hay).map(|m| (m.start(), m.end())),
+        }
+    }
+
+    /// Marks rows that contain a required literal (and therefore may match).
+    ///
+    /// `data`/`offsets` are the raw buffers of a Utf8/LargeUtf8 array; the scan runs over
+    /// the concatenated values and maps hits back to rows. Occurrences straddling a row
+    /// boundary are ignored. Null slots are not special-cased; callers handle them first.
+    pub fn candidates<O: OffsetSizeTrait>(&self, data: &[u8], offsets: &[O]) -> Vec<bool> {
+        let n = offsets.len().saturating_sub(1);
+        let mut out = vec![false; n];
+        if n == 0 {
+            return out;
+        }
+
+        let end = offsets[n].as_usize();
+        let mut pos = offsets[0].as_usize();
+        let mut row = 0;
+        while pos < end {
+            let Some((s, e)) = self.find(&data[pos..end]) else {
+                break;
+            };
+            let (s, e) = (pos + s, pos + e);
+
+            // offsets[n] == end > s, so this stops at a valid row
+            while offsets[row + 1].as_usize() <= s {
+                row += 1;
+            }
+            let row_end = offsets[row + 1].as_usize();
+            if e <= row_end {
+                out[row] = true;
+                pos = row_end;
+            } else {
+                pos = s + 1;
+            }
+        }
+        out
+    }
+}
+
+/// Literals of which at least one must occur in any match of `hir`.
+fn required_literals(hir: &Hir) -> Option<Vec<Vec<u8>>> {
+    match hir.kind() {
+        HirKind::Literal(lit) => Some(vec![lit.0.to_vec()]),
+        HirKind::Capture(cap) => required_literals(&cap.sub),
+        HirKind::Repetition(rep) if rep.min >= 1 => required_literals(&rep.sub),
+        // Any child's requirement holds for the whole concatenation; keep the most selective.
+        HirKind::Concat(subs) => subs
+            .iter()
+            .filter_map(required_literals)
+            .max_by_key(|lits| {
+                let shortest = lits.iter().map(Vec::len).min().unwrap_or(0);
+                (shortest, std::cmp::Reverse(lits.len()))
+            }),
+        // Every branch must contribute, otherwise a branch could match without any literal.
+        HirKind::Alternation(subs) => {
+            let mut all = Vec::new();
+            for sub in subs {
+                all.extend(required_literals(sub)?);
+                if all.len() > MAX_ALTERNATIVES {
+                    return None;
+                }
+            }
+            Some(all)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lits(pat: &str) -> Option<Vec<Vec<u8>>> {
+        required_literals(&regex_syntax::parse(pat).unwrap())
+    }
+
+    #[test]
+    fn extracts_required_literals() {
+        assert_eq!(lits(r"user_id=(\d+)"), Some(vec![b"user_id=".to_vec()]));
+        assert_eq!(
+            lits(r"(?:GET|POST) /(\w+)"),
+            Some(vec![b"GET".to_vec(), b"POST".to_vec()]) // longer than the shared " /"
+        );
+        assert_eq!(
+            lits(r"(foo|bar)\d"),
+            Some(vec![b"foo".to_vec(), b"bar".to_vec()])
+        );
+        assert_eq!(lits(r"(\d+)-(\d+)"), Some(vec![b"-".to_vec()]));
+        assert_eq!(lits(r"(abc)?\d+"), None);
+        assert_eq!(lits(r"(foo|\d)"), None);
+        assert_eq!(lits(r""), None);
+    }
+
+    #[test]
+    fn candidates_map_hits_to_rows() {
+        let pf = Prefilter::new(r"id=(\d+)").unwrap();
+        // rows: "xid=1", "", "i", "d=2", "id=3id=4"
+        let data = b"xid=1id=2id=3id=4";
+        let offsets: [i32; 6] = [0, 5, 5, 6, 9, 17];
+        assert_eq!(
+            pf.candidates(data, &offsets),
+            vec![true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn candidates_with_alternatives() {
+        let pf = Prefilter::new(r"(cat|dog)s").unwrap();
+        let data = b"hotdogscatsnone";
+        let offsets: [i64; 4] = [0, 7, 11, 15];
+        assert_eq!(pf.candidates(data, &offsets), vec![true, true, false]);
+    }
+}
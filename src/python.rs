@@ -0,0 +1,83 @@
+//! PyO3 bindings: `regexp_extract` for `datafusion-python`.
+//!
+//! [`register`] adds `regexp_extract_udf(config=None, **kwargs)` to a Python module; the
+//! `python-ext` workspace member builds the extension module `datafusion_regexp_extract_udf`
+//! from it. The function returns an object exposing `__datafusion_scalar_udf__`, the
+//! PyCapsule protocol `datafusion-python` uses for native UDFs. The capsule holds a
+//! `datafusion-ffi` `FFI_ScalarUDF`:
+//!
+//! ```text
+//! from datafusion import SessionContext, udf
+//! from datafusion_regexp_extract_udf import regexp_extract_udf
+//!
+//! ctx = SessionContext()
+//! ctx.register_udf(udf(regexp_extract_udf(invalid_pattern_mode="collect")))
+//! ```
+//!
+//! The keys of the `config` dict and the keyword arguments are those
+//! `RegexpExtractConfig::set` accepts, keyword arguments overriding the dict; values are
+//! converted with `str()` (`True`/`False` as `true`/`false`). As with the `ffi` plugin, an
+//! `ErrorLog` or `SharedPatternCache` cannot be attached from Python.
+
+use crate::config::RegexpExtractConfig;
+use crate::udf::regexp_extract_udf_with;
+use datafusion::logical_expr::ScalarUDF;
+use datafusion_ffi::udf::FFI_ScalarUDF;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyCapsule, PyDict};
+use std::sync::Arc;
+
+/// Name `datafusion-python` checks on the capsule.
+const CAPSULE_NAME: &std::ffi::CStr = c"datafusion_scalar_udf";
+
+/// `regexp_extract` with a fixed config, registrable with `datafusion-python`.
+#[pyclass(name = "RegexpExtractUdf", frozen)]
+pub struct PyRegexpExtractUdf {
+    udf: Arc<ScalarUDF>,
+}
+
+#[pymethods]
+impl PyRegexpExtractUdf {
+    fn __datafusion_scalar_udf__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+        PyCapsule::new(
+            py,
+            FFI_ScalarUDF::from(self.udf.clone()),
+            Some(CAPSULE_NAME.into()),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RegexpExtractUdf({})", self.udf.name())
+    }
+}
+
+/// Builds `regexp_extract` from a `config` dict and keyword arguments; an unknown key or a
+/// bad value raises `ValueError`.
+#[pyfunction]
+#[pyo3(signature = (config=None, **kwargs))]
+pub fn regexp_extract_udf(
+    config: Option<&Bound<'_, PyDict>>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyRegexpExtractUdf> {
+    let mut cfg = RegexpExtractConfig::new();
+    for (key, value) in config.into_iter().chain(kwargs).flatten() {
+        let key: String = key.extract()?;
+        let value = match value.downcast::<PyBool>() {
+            Ok(b) => b.is_true().to_string(),
+            Err(_) => value.str()?.to_string(),
+        };
+        cfg.set(&key, &value)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    }
+    Ok(PyRegexpExtractUdf {
+        udf: Arc::new(regexp_extract_udf_with(cfg)),
+    })
+}
+
+/// Adds `RegexpExtractUdf` and `regexp_extract_udf` to `m`.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyRegexpExtractUdf>()?;
+    m.add_function(wrap_pyfunction!(regexp_extract_udf, m)?)?;
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use crate::error::RegexpExtractError;
+
 #[cfg(feature = "fancy-regex")]
 pub use fancy_regex as engine;
 #[cfg(not(feature = "fancy-regex"))]
@@ -15,14 +17,63 @@ pub type RegexError = fancy_regex::Error;
 #[cfg(not(feature = "fancy-regex"))]
 pub type RegexError = regex::Error;
 
+/// Name of the engine behind [`Regex`], as reported in errors.
+#[cfg(feature = "fancy-regex")]
+pub const ENGINE: &str = "fancy-regex";
+#[cfg(not(feature = "fancy-regex"))]
+pub const ENGINE: &str = "regex";
+
+/// Name of the engine behind [`BytesRegex`].
+pub const BYTES_ENGINE: &str = "regex::bytes";
+
 #[inline]
 pub fn compile(pat: &str) -> Result<Regex, Box<RegexError>> {
     engine::Regex::new(pat).map_err(Box::new)
 }
 
+/// [`RegexpExtractError::InvalidPattern`] for `pat` rejected by [`compile`].
+pub fn pattern_error(pat: &str, err: Box<RegexError>) -> RegexpExtractError {
+    #[cfg(feature = "fancy-regex")]
+    let span = match *err {
+        fancy_regex::Error::ParseError(pos, _) => {
+            let len = pat
+                .get(pos..)
+                .and_then(|t| t.chars().next())
+                .map_or(0, char::len_utf8);
+            Some(pos..pos + len)
+        }
+        _ => None,
+    };
+    #[cfg(not(feature = "fancy-regex"))]
+    let span = syntax_span(pat, true);
+    RegexpExtractError::InvalidPattern {
+        pattern: pat.to_string(),
+        span,
+        engine: ENGINE,
+        source: err,
+    }
+}
+
+/// `regex` only reports syntax errors as text, so the pattern is parsed again with
+/// `regex-syntax` (the same parser) to recover the offending span.
+#[cfg(not(feature = "fancy-regex"))]
+fn syntax_span(pat: &str, utf8: bool) -> Option<std::ops::Range<usize>> {
+    let err = regex_syntax::ParserBuilder::new()
+        .utf8(utf8)
+        .build()
+        .parse(pat)
+        .err()?;
+    let span = match &err {
+        regex_syntax::Error::Parse(e) => e.span(),
+        regex_syntax::Error::Translate(e) => e.span(),
+        _ => return None,
+    };
+    Some(span.start.offset..span.end.offset)
+}
+
 /// Always return `Result<Option<Captures>>` so callers handle both engines uniformly.
 #[inline]
-pub fn captures<'a>(re: &'a Regex, s: &'a str) -> Result<Option<Captures<'a>>, Box<RegexError>> {
+pub fn captures<'h>(re: &Regex, s: &'h str) -> Result<Option<Captures<'h>>, Box<RegexError>> {
     #[cfg(feature = "fancy-regex")]
     {
         re.captures(s).map_err(Box::new)
@@ -32,3 +83,271 @@ pub fn captures<'a>(re: &'a Regex, s: &'a str) -> Result<Option<Captures<'a>>, B
         Ok(re.captures(s))
     }
 }
+
+/// Capture-slot buffer reused across rows, so matching does not allocate a `Captures` per row.
+///
+/// Must be created from a regex with as many capture groups as the one it is used with
+/// (see [`capture_buf`]).
+#[cfg(not(feature = "fancy-regex"))]
+pub struct CaptureBuf(regex::CaptureLocations);
+
+/// Capture-slot buffer reused across rows. `fancy-regex` has no `captures_read`: every
+/// match still builds a `Captures` (and its slot `Vec`) inside the engine, and the spans
+/// are copied out of it, so with this engine the per-row allocation remains.
+#[cfg(feature = "fancy-regex")]
+pub struct CaptureBuf(Vec<Option<(usize, usize)>>);
+
+impl CaptureBuf {
+    /// Byte span of group `i` from the last successful [`captures_read`].
+    #[inline]
+    pub fn get(&self, i: usize) -> Option<(usize, usize)> {
+        #[cfg(feature = "fancy-regex")]
+        {
+            self.0.get(i).copied().flatten()
+        }
+        #[cfg(not(feature = "fancy-regex"))]
+        {
+            self.0.get(i)
+        }
+    }
+}
+
+#[inline]
+pub fn capture_buf(re: &Regex) -> CaptureBuf {
+    #[cfg(feature = "fancy-regex")]
+    {
+        CaptureBuf(Vec::with_capacity(re.captures_len()))
+    }
+    #[cfg(not(feature = "fancy-regex"))]
+    {
+        CaptureBuf(re.capture_locations())
+    }
+}
+
+/// Fills `buf` with the group spans of the first match; `false` when nothing matched.
+#[inline]
+pub fn captures_read(re: &Regex, buf: &mut CaptureBuf, s: &str) -> Result<bool, Box<RegexError>> {
+    #[cfg(feature = "fancy-regex")]
+    {
+        let Some(caps) = re.captures(s).map_err(Box::new)? else {
+            return Ok(false);
+        };
+        buf.0.clear();
+        buf.0
+            .extend((0..caps.len()).map(|i| caps.get(i).map(|m| (m.start(), m.end()))));
+        Ok(true)
+    }
+    #[cfg(not(feature = "fancy-regex"))]
+    {
+        Ok(re.captures_read(&mut buf.0, s).is_some())
+    }
+}
+
+/// Text of the leftmost-first match, without resolving capture groups.
+///
+/// With `regex` this runs on the lazy DFA instead of the PikeVM/backtracker.
+#[inline]
+pub fn find<'h>(re: &Regex, s: &'h str) -> Result<Option<&'h str>, Box<RegexError>> {
+    #[cfg(feature = "fancy-regex")]
+    {
+        Ok(re.find(s).map_err(Box::new)?.map(|m| m.as_str()))
+    }
+    #[cfg(not(feature = "fancy-regex"))]
+    {
+        Ok(re.find(s).map(|m| m.as_str()))
+    }
+}
+
+/// Byte span of the first match starting the search at byte offset `start`.
+///
+/// Unlike slicing the haystack, anchors and word boundaries still see the text before `start`.
+#[inline]
+pub fn find_at(
+    re: &Regex,
+    s: &str,
+    start: usize,
+) -> Result<Option<(usize, usize)>, Box<RegexError>> {
+    #[cfg(feature = "fancy-regex")]
+    {
+        Ok(re
+            .find_from_pos(s, start)
+            .map_err(Box::new)?
+            .map(|m| (m.start(), m.end())))
+    }
+    #[cfg(not(feature = "fancy-regex"))]
+    {
+        Ok(re.find_at(s, start).map(|m| (m.start(), m.end())))
+    }
+}
+
+/// Existence check for callers that never look at the match itself.
+///
+/// With `regex` this uses `shortest_match`, which stops at the first accepting state.
+#[inline]
+pub fn is_match(re: &Regex, s: &str) -> Result<bool, Box<RegexError>> {
+    #[cfg(feature = "fancy-regex")]
+    {
+        re.is_match(s).map_err(Box::new)
+    }
+    #[cfg(not(feature = "fancy-regex"))]
+    {
+        Ok(re.shortest_match(s).is_some())
+    }
+}
+
+/// Byte-oriented regex for `Binary` input.
+///
+/// Always `regex::bytes`: `fancy-regex` has no byte API, so look-around and
+/// backreferences are not available for binary columns.
+pub type BytesRegex = regex::bytes::Regex;
+
+/// Compiles `pat` for matching raw bytes; `(?-u)` in the pattern matches arbitrary
+/// (non-UTF-8) bytes with `.` and classes.
+pub fn compile_bytes(pat: &str) -> Result<BytesRegex, RegexpExtractError> {
+    #[cfg(feature = "fancy-regex")]
+    {
+        BytesRegex::new(pat).map_err(|e| match compile(pat) {
+            // valid for fancy-regex, e.g. look-around: only the byte engine rejects it
+            Ok(_) => RegexpExtractError::UnsupportedBinaryPattern(e.to_string()),
+            Err(fancy) => pattern_error(pat, fancy),
+        })
+    }
+    #[cfg(not(feature = "fancy-regex"))]
+    {
+        BytesRegex::new(pat).map_err(|e| RegexpExtractError::InvalidPattern {
+            pattern: pat.to_string(),
+            span: syntax_span(pat, false),
+            engine: BYTES_ENGINE,
+            source: Box::new(e),
+        })
+    }
+}
+
+/// Byte span of group `idx` in the first match in `s`; `None` when nothing matched or
+/// the group did not participate. `locs` must come from `re.capture_locations()`.
+#[inline]
+pub fn extract_bytes(
+    re: &BytesRegex,
+    locs: &mut regex::bytes::CaptureLocations,
+    s: &[u8],
+    idx: usize,
+) -> Option<(usize, usize)> {
+    if idx == 0 {
+        return re.find(s).map(|m| (m.start(), m.end()));
+    }
+    re.captures_read(locs, s)?;
+    locs.get(idx)
+}
+
+/// Many patterns matched in a single pass over the haystack.
+///
+/// Backed by `regex::RegexSet`; `fancy-regex` has no set type, so there each member
+/// is tried in turn.
+#[derive(Debug)]
+pub struct PatternSet {
+    #[cfg(not(feature = "fancy-regex"))]
+    set: regex::RegexSet,
+    #[cfg(feature = "fancy-regex")]
+    members: Vec<Regex>,
+}
+
+impl PatternSet {
+    pub fn new<S: AsRef<str>>(pats: &[S]) -> Result<Self, Box<RegexError>> {
+        #[cfg(feature = "fancy-regex")]
+        {
+            let members = pats
+                .iter()
+                .map(|p| compile(p.as_ref()))
+                .collect::<Result<_, _>>()?;
+            Ok(Self { members })
+        }
+        #[cfg(not(feature = "fancy-regex"))]
+        {
+            let set = regex::RegexSet::new(pats.iter().map(AsRef::as_ref)).map_err(Box::new)?;
+            Ok(Self { set })
+        }
+    }
+
+    /// Set of the patterns that compile, each compiled once, skipping the others. Returns
+    /// one entry per pattern: `None` for a member, the compile error otherwise. `Err` only
+    /// when the members together exceed the engine's size limits.
+    #[allow(clippy::type_complexity)]
+    pub fn new_lenient<S: AsRef<str>>(
+        pats: &[S],
+    ) -> Result<(Self, Vec<Option<Box<RegexError>>>), Box<RegexError>> {
+        #[cfg(feature = "fancy-regex")]
+        {
+            let mut members = Vec::with_capacity(pats.len());
+            let errors = pats
+                .iter()
+                .map(|p| match compile(p.as_ref()) {
+                    Ok(re) => {
+                        members.push(re);
+                        None
+                    }
+                    Err(e) => Some(e),
+                })
+                .collect();
+            Ok((Self { members }, errors))
+        }
+        #[cfg(not(feature = "fancy-regex"))]
+        {
+            if let Ok(set) = regex::RegexSet::new(pats.iter().map(AsRef::as_ref)) {
+                return Ok((Self { set }, pats.iter().map(|_| None).collect()));
+            }
+            // The set parses every pattern before compiling any, so finding the invalid
+            // ones by parsing does not compile the valid ones twice.
+            let errors = pats
+                .iter()
+                .map(|p| match regex_syntax::parse(p.as_ref()) {
+                    Ok(_) => None,
+                    Err(_) => compile(p.as_ref()).err(),
+                })
+                .collect::<Vec<_>>();
+            let valid = pats
+                .iter()
+                .zip(&errors)
+                .filter(|(_, e)| e.is_none())
+                .map(|(p, _)| p.as_ref());
+            let set = regex::RegexSet::new(valid).map_err(Box::new)?;
+            Ok((Self { set }, errors))
+        }
+    }
+
+    /// Appends the indices of all members matching `s` to `out`, in pattern order.
+    #[inline]
+    pub fn matches_into(&self, s: &str, out: &mut Vec<usize>) -> Result<(), Box<RegexError>> {
+        #[cfg(feature = "fancy-regex")]
+        {
+            for (i, re) in self.members.iter().enumerate() {
+                if re.is_match(s).map_err(Box::new)? {
+                    out.push(i);
+                }
+            }
+            Ok(())
+        }
+        #[cfg(not(feature = "fancy-regex"))]
+        {
+            out.extend(self.set.matches(s).iter());
+            Ok(())
+        }
+    }
+
+    /// Index of the first member (in pattern order) matching `s`.
+    #[inline]
+    pub fn first_match(&self, s: &str) -> Result<Option<usize>, Box<RegexError>> {
+        #[cfg(feature = "fancy-regex")]
+        {
+            for (i, re) in self.members.iter().enumerate() {
+                if re.is_match(s).map_err(Box::new)? {
+                    return Ok(Some(i));
+                }
+            }
+            Ok(None)
+        }
+        #[cfg(not(feature = "fancy-regex"))]
+        {
+            Ok(self.set.matches(s).iter().next())
+        }
+    }
+}
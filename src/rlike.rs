@@ -0,0 +1,242 @@
+//! `rlike(str, pattern)`: Spark's boolean regex predicate (`str RLIKE pattern`).
+//!
+//! True when `pattern` matches anywhere in `str`, like Java's `Matcher.find()`. Shares the
+//! string-array abstractions, pattern cache and literal prefilter with `regexp_extract`,
+//! but only asks the engine whether a match exists, so no capture work is done.
+
+use crate::config::RegexpExtractConfig;
+use crate::error::RegexpExtractError;
+use crate::kernel::{
+    ScalarPattern, StrArray, check_lengths, in_batch, row_failure, scalar_candidates,
+};
+use crate::pattern_cache::PatternCache;
+use crate::re::{Regex, is_match};
+use crate::session::Reconfigure;
+use crate::types::to_array;
+use datafusion::arrow::array::{Array, ArrayRef, BooleanBuilder};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::{as_large_string_array, as_string_array};
+use datafusion::common::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+pub fn rlike_udf_with(cfg: RegexpExtractConfig) -> ScalarUDF {
+    ScalarUDF::from(RlikeUdf::new_with(cfg))
+}
+
+pub fn rlike_udf() -> ScalarUDF {
+    rlike_udf_with(RegexpExtractConfig::default())
+}
+
+/// Internal implementation of the `rlike` UDF
+#[derive(Debug)]
+pub(crate) struct RlikeUdf {
+    signature: Signature,
+    aliases: Vec<String>,
+    cfg: Arc<RegexpExtractConfig>,
+}
+
+impl RlikeUdf {
+    fn new_with(cfg: RegexpExtractConfig) -> Self {
+        // Accept (Utf8|LargeUtf8, Utf8|LargeUtf8)
+        let combos = [
+            (DataType::Utf8, DataType::Utf8),
+            (DataType::Utf8, DataType::LargeUtf8),
+            (DataType::LargeUtf8, DataType::Utf8),
+            (DataType::LargeUtf8, DataType::LargeUtf8),
+        ];
+
+        let sig = Signature::one_of(
+            combos
+                .into_iter()
+                .map(|(a, b)| TypeSignature::Exact(vec![a, b]))
+                .collect(),
+            Volatility::Immutable,
+        );
+
+        Self {
+            signature: sig,
+            aliases: vec!["regexp_like".to_string()],
+            cfg: Arc::new(cfg),
+        }
+    }
+}
+
+impl Reconfigure for RlikeUdf {
+    fn config(&self) -> &RegexpExtractConfig {
+        &self.cfg
+    }
+
+    fn with_config(&self, cfg: RegexpExtractConfig) -> ScalarUDF {
+        ScalarUDF::from(Self::new_with(cfg))
+    }
+}
+
+impl ScalarUDFImpl for RlikeUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "rlike"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        evaluate_rlike_with(args, self.cfg.as_ref())
+    }
+}
+
+pub fn evaluate_rlike_with(
+    args: ScalarFunctionArgs,
+    cfg: &RegexpExtractConfig,
+) -> Result<ColumnarValue> {
+    in_batch(cfg, |cfg| evaluate(args, cfg))
+}
+
+fn evaluate(args: ScalarFunctionArgs, cfg: &RegexpExtractConfig) -> Result<ColumnarValue> {
+    let rows = args.number_rows;
+    let a = &args.args;
+
+    if a.len() != 2 {
+        return Err(DataFusionError::Execution(format!(
+            "rlike expects 2 arguments, got {}",
+            a.len()
+        )));
+    }
+
+    let s_arr = to_array(&a[0], rows)?;
+    // keep a scalar pattern as a length-1 array so it is compiled once
+    let p_arr = match &a[1] {
+        ColumnarValue::Scalar(sv) => sv.to_array()?,
+        _ => to_array(&a[1], rows)?,
+    };
+    check_lengths(s_arr.len(), &[("pattern", p_arr.len())])?;
+
+    let out = match (s_arr.data_type(), p_arr.data_type()) {
+        (DataType::Utf8, DataType::Utf8) => {
+            run_rlike(as_string_array(&s_arr)?, as_string_array(&p_arr)?, cfg)
+        }
+        (DataType::Utf8, DataType::LargeUtf8) => run_rlike(
+            as_string_array(&s_arr)?,
+            as_large_string_array(&p_arr)?,
+            cfg,
+        ),
+        (DataType::LargeUtf8, DataType::Utf8) => run_rlike(
+            as_large_string_array(&s_arr)?,
+            as_string_array(&p_arr)?,
+            cfg,
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => run_rlike(
+            as_large_string_array(&s_arr)?,
+            as_large_string_array(&p_arr)?,
+            cfg,
+        ),
+        (s_dt, p_dt) => {
+            return Err(DataFusionError::Execution(format!(
+                "rlike expects Utf8 or LargeUtf8 arguments, got ({s_dt:?}, {p_dt:?})"
+            )));
+        }
+    }
+    .map_err(DataFusionError::from)?;
+
+    Ok(ColumnarValue::Array(out))
+}
+
+/// Boolean kernel. NULL `str`/`pattern` gives NULL; an invalid pattern (or a runtime
+/// match error) gives `false` in `InvalidPatternMode::EmptyString` and NULL in `Collect`.
+fn run_rlike<S, P>(
+    strings: &S,
+    patterns: &P,
+    cfg: &RegexpExtractConfig,
+) -> Result<ArrayRef, RegexpExtractError>
+where
+    S: StrArray,
+    P: StrArray,
+{
+    let n = strings.len();
+    let mut b = BooleanBuilder::with_capacity(n);
+    let mut cache = PatternCache::new(cfg.cache_size).with_shared(cfg.pattern_cache.clone());
+
+    let pat_scalar = patterns.len() == 1;
+    let scalar = if pat_scalar && !patterns.is_null(0) {
+        Some(ScalarPattern::<Regex>::compile(patterns.value(0), cfg)?)
+    } else {
+        None
+    };
+    let compiled = scalar.as_ref().is_some_and(|p| p.regex().is_some());
+    let candidates = scalar_candidates(strings, patterns, compiled);
+
+    for i in 0..n {
+        let pat_is_null = patterns.is_null(if pat_scalar { 0 } else { i });
+        if strings.is_null(i) || pat_is_null {
+            b.append_null();
+            continue;
+        }
+
+        let pat = patterns.value(if pat_scalar { 0 } else { i });
+        let re: &Regex = if let Some(scalar) = &scalar {
+            match scalar.regex() {
+                Some(re) => re,
+                None => {
+                    b.append_option(scalar.failure(i, cfg).resolve(Some(false), None)?);
+                    continue;
+                }
+            }
+        } else {
+            match cache.get_or_compile(pat) {
+                Ok(r) => r,
+                Err(e) => {
+                    b.append_option(row_failure(cfg, i, pat, e).resolve(Some(false), None)?);
+                    continue;
+                }
+            }
+        };
+
+        if candidates.as_ref().is_some_and(|c| !c[i]) {
+            b.append_value(false);
+            continue;
+        }
+
+        match is_match(re, strings.value(i)) {
+            Ok(m) => b.append_value(m),
+            Err(e) => {
+                let err = RegexpExtractError::MatchError(e.to_string());
+                b.append_option(row_failure(cfg, i, pat, err).resolve(Some(false), None)?);
+            }
+        }
+    }
+
+    Ok(Arc::new(b.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn udf_shape_is_correct() {
+        let f = rlike_udf();
+        assert_eq!(f.name(), "rlike");
+        assert_eq!(f.aliases(), &["regexp_like".to_string()]);
+        let dt = f
+            .return_type(&[DataType::LargeUtf8, DataType::Utf8])
+            .unwrap();
+        assert_eq!(dt, DataType::Boolean);
+    }
+}
@@ -0,0 +1,483 @@
+//! Spark 3.4 search functions: `regexp_count`, `regexp_instr` and `regexp_substr`.
+//!
+//! These follow Spark (Java regex) results rather than Rust iteration conventions:
+//! - `regexp_count(str, pattern)`: number of matches found by repeated `Matcher.find()`.
+//!   Java reports an empty match right after a non-empty one (`a*` finds 4 matches in
+//!   `baaab`), which `find_iter` would skip, so matches are walked by hand.
+//! - `regexp_instr(str, pattern[, idx])`: 1-based position of group `idx` (default 0) of
+//!   the first match, 0 when nothing matched or the group did not participate. Like Spark,
+//!   an `idx` above the pattern's group count is an error once a row matches
+//!   (`RegexpExtractError::GroupIndexOutOfRange`; NULL under `Collect`). Positions are Java
+//!   string indices, i.e. UTF-16 code units: characters outside the BMP count twice.
+//!   `regexp_extract_span` reports offsets in the same unit.
+//! - `regexp_substr(str, pattern)`: text of the first match; NULL when nothing matched or
+//!   the match is empty (Spark defines it as `nullif(regexp_extract(str, pattern, 0), '')`).
+//!
+//! Counts and positions are `Int32` for `Utf8` input and `Int64` for `LargeUtf8`.
+//! NULL inputs give NULL; in `InvalidPatternMode::EmptyString` an invalid pattern behaves
+//! as "no match" (0 / 0 / NULL), in `Collect` the row is NULL.
+
+use crate::config::RegexpExtractConfig;
+use crate::error::RegexpExtractError;
+use crate::kernel::{
+    GroupReader, ScalarPattern, StrArray, StrBuilder, check_lengths, in_batch, row_failure,
+};
+use crate::pattern_cache::PatternCache;
+use crate::re::{Regex, RegexError, captures_read, find, find_at};
+use crate::session::Reconfigure;
+use crate::types::to_array;
+use datafusion::arrow::array::{Array, ArrayRef, Int32Array, Int64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::{
+    as_int32_array, as_int64_array, as_large_string_array, as_string_array,
+};
+use datafusion::common::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+pub fn regexp_count_udf_with(cfg: RegexpExtractConfig) -> ScalarUDF {
+    ScalarUDF::from(RegexpSearchUdf::new_with(Search::Count, cfg))
+}
+
+pub fn regexp_count_udf() -> ScalarUDF {
+    regexp_count_udf_with(RegexpExtractConfig::default())
+}
+
+pub fn regexp_instr_udf_with(cfg: RegexpExtractConfig) -> ScalarUDF {
+    ScalarUDF::from(RegexpSearchUdf::new_with(Search::Instr, cfg))
+}
+
+pub fn regexp_instr_udf() -> ScalarUDF {
+    regexp_instr_udf_with(RegexpExtractConfig::default())
+}
+
+pub fn regexp_substr_udf_with(cfg: RegexpExtractConfig) -> ScalarUDF {
+    ScalarUDF::from(RegexpSearchUdf::new_with(Search::Substr, cfg))
+}
+
+pub fn regexp_substr_udf() -> ScalarUDF {
+    regexp_substr_udf_with(RegexpExtractConfig::default())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Search {
+    Count,
+    Instr,
+    Substr,
+}
+
+impl Search {
+    fn name(self) -> &'static str {
+        match self {
+            Search::Count => "regexp_count",
+            Search::Instr => "regexp_instr",
+            Search::Substr => "regexp_substr",
+        }
+    }
+}
+
+/// Internal implementation of `regexp_count` / `regexp_instr` / `regexp_substr`
+#[derive(Debug)]
+pub(crate) struct RegexpSearchUdf {
+    search: Search,
+    signature: Signature,
+    cfg: Arc<RegexpExtractConfig>,
+}
+
+impl RegexpSearchUdf {
+    fn new_with(search: Search, cfg: RegexpExtractConfig) -> Self {
+        // Accept (Utf8|LargeUtf8, Utf8|LargeUtf8), plus an Int32|Int64 group for regexp_instr
+        let strs = [DataType::Utf8, DataType::LargeUtf8];
+        let mut sigs = Vec::new();
+        for s in &strs {
+            for p in &strs {
+                sigs.push(TypeSignature::Exact(vec![s.clone(), p.clone()]));
+                if search == Search::Instr {
+                    for i in [DataType::Int32, DataType::Int64] {
+                        sigs.push(TypeSignature::Exact(vec![s.clone(), p.clone(), i]));
+                    }
+                }
+            }
+        }
+
+        Self {
+            search,
+            signature: Signature::one_of(sigs, Volatility::Immutable),
+            cfg: Arc::new(cfg),
+        }
+    }
+}
+
+impl Reconfigure for RegexpSearchUdf {
+    fn config(&self) -> &RegexpExtractConfig {
+        &self.cfg
+    }
+
+    fn with_config(&self, cfg: RegexpExtractConfig) -> ScalarUDF {
+        ScalarUDF::from(Self::new_with(self.search, cfg))
+    }
+}
+
+impl ScalarUDFImpl for RegexpSearchUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.search.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match (self.search, arg_types.first()) {
+            (Search::Substr, Some(DataType::Utf8)) => Ok(DataType::Utf8),
+            (Search::Substr, Some(DataType::LargeUtf8)) => Ok(DataType::LargeUtf8),
+            (_, Some(DataType::Utf8)) => Ok(DataType::Int32),
+            (_, Some(DataType::LargeUtf8)) => Ok(DataType::Int64),
+            (_, other) => Err(DataFusionError::Plan(format!(
+                "{} expects first argument Utf8 or LargeUtf8, got: {other:?}",
+                self.name()
+            ))),
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        in_batch(self.cfg.as_ref(), |cfg| {
+            evaluate_search(self.search, args, cfg)
+        })
+    }
+}
+
+fn evaluate_search(
+    search: Search,
+    args: ScalarFunctionArgs,
+    cfg: &RegexpExtractConfig,
+) -> Result<ColumnarValue> {
+    let name = search.name();
+    let rows = args.number_rows;
+    let a = &args.args;
+
+    let arity_ok = match search {
+        Search::Instr => a.len() == 2 || a.len() == 3,
+        _ => a.len() == 2,
+    };
+    if !arity_ok {
+        return Err(DataFusionError::Execution(format!(
+            "{name} got an unexpected number of arguments: {}",
+            a.len()
+        )));
+    }
+
+    let s_arr = to_array(&a[0], rows)?;
+    // keep scalar pattern/idx as length-1 arrays so they are handled once
+    let p_arr = match &a[1] {
+        ColumnarValue::Scalar(sv) => sv.to_array()?,
+        _ => to_array(&a[1], rows)?,
+    };
+    let i_arr = match a.get(2) {
+        Some(ColumnarValue::Scalar(sv)) => Some(sv.to_array()?),
+        Some(cv) => Some(to_array(cv, rows)?),
+        None => None,
+    };
+    let idx_len = i_arr.as_ref().map_or(1, |i| i.len());
+    check_lengths(s_arr.len(), &[("pattern", p_arr.len()), ("idx", idx_len)])?;
+    let idx = match &i_arr {
+        None => Idx::Zero,
+        Some(i) => match i.data_type() {
+            DataType::Int64 => Idx::I64(as_int64_array(i)?),
+            DataType::Int32 => Idx::I32(as_int32_array(i)?),
+            other => {
+                return Err(DataFusionError::Execution(format!(
+                    "{name} idx must be Int32 or Int64, got {other:?}"
+                )));
+            }
+        },
+    };
+
+    let out = match (s_arr.data_type(), p_arr.data_type()) {
+        (DataType::Utf8, DataType::Utf8) => run_search(
+            search,
+            as_string_array(&s_arr)?,
+            as_string_array(&p_arr)?,
+            idx,
+            cfg,
+        ),
+        (DataType::Utf8, DataType::LargeUtf8) => run_search(
+            search,
+            as_string_array(&s_arr)?,
+            as_large_string_array(&p_arr)?,
+            idx,
+            cfg,
+        ),
+        (DataType::LargeUtf8, DataType::Utf8) => run_search(
+            search,
+            as_large_string_array(&s_arr)?,
+            as_string_array(&p_arr)?,
+            idx,
+            cfg,
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => run_search(
+            search,
+            as_large_string_array(&s_arr)?,
+            as_large_string_array(&p_arr)?,
+            idx,
+            cfg,
+        ),
+        (s_dt, p_dt) => {
+            return Err(DataFusionError::Execution(format!(
+                "{name} expects Utf8 or LargeUtf8 arguments, got ({s_dt:?}, {p_dt:?})"
+            )));
+        }
+    }
+    .map_err(DataFusionError::from)?;
+
+    Ok(ColumnarValue::Array(out))
+}
+
+/// Group index argument of `regexp_instr` (scalar when length 1).
+#[derive(Clone, Copy)]
+enum Idx<'a> {
+    Zero,
+    I32(&'a Int32Array),
+    I64(&'a Int64Array),
+}
+
+impl Idx<'_> {
+    /// Group index for row `i`; `None` when it is null.
+    #[inline]
+    fn at(self, i: usize) -> Option<i64> {
+        match self {
+            Idx::Zero => Some(0),
+            Idx::I32(a) => {
+                let i = if a.len() == 1 { 0 } else { i };
+                (!a.is_null(i)).then(|| a.value(i) as i64)
+            }
+            Idx::I64(a) => {
+                let i = if a.len() == 1 { 0 } else { i };
+                (!a.is_null(i)).then(|| a.value(i))
+            }
+        }
+    }
+}
+
+/// Per-row result before it is written to the output array.
+enum Out<'h> {
+    Null,
+    Int(i64),
+    Str(&'h str),
+}
+
+fn run_search<S, P>(
+    search: Search,
+    strings: &S,
+    patterns: &P,
+    idx: Idx<'_>,
+    cfg: &RegexpExtractConfig,
+) -> Result<ArrayRef, RegexpExtractError>
+where
+    S: StrArray,
+    P: StrArray,
+{
+    let n = strings.len();
+    let mut cache = PatternCache::new(cfg.cache_size).with_shared(cfg.pattern_cache.clone());
+    let mut out: Vec<Out<'_>> = Vec::with_capacity(n);
+
+    // result for failed rows in `EmptyString` mode
+    let no_match = || match search {
+        Search::Count | Search::Instr => Out::Int(0),
+        Search::Substr => Out::Null,
+    };
+
+    let mut reader = GroupReader::new();
+    let pat_scalar = patterns.len() == 1;
+    let scalar = if pat_scalar && !patterns.is_null(0) {
+        Some(ScalarPattern::<Regex>::compile(patterns.value(0), cfg)?)
+    } else {
+        None
+    };
+
+    for i in 0..n {
+        let pat_is_null = patterns.is_null(if pat_scalar { 0 } else { i });
+        if strings.is_null(i) || pat_is_null {
+            out.push(Out::Null);
+            continue;
+        }
+        let Some(group) = idx.at(i) else {
+            out.push(Out::Null);
+            continue;
+        };
+        let pat = patterns.value(if pat_scalar { 0 } else { i });
+        if group < 0 {
+            let err = RegexpExtractError::NegativeIndex(group);
+            out.push(row_failure(cfg, i, pat, err).resolve(no_match(), Out::Null)?);
+            continue;
+        }
+
+        let re: &Regex = if let Some(scalar) = &scalar {
+            match scalar.regex() {
+                Some(re) => re,
+                None => {
+                    out.push(scalar.failure(i, cfg).resolve(no_match(), Out::Null)?);
+                    continue;
+                }
+            }
+        } else {
+            match cache.get_or_compile(pat) {
+                Ok(r) => r,
+                Err(e) => {
+                    out.push(row_failure(cfg, i, pat, e).resolve(no_match(), Out::Null)?);
+                    continue;
+                }
+            }
+        };
+
+        let s = strings.value(i);
+        let match_error = |e: Box<RegexError>| RegexpExtractError::MatchError(e.to_string());
+        let res = match search {
+            Search::Count => count_matches(re, s).map(Out::Int).map_err(match_error),
+            Search::Instr => group_position(&mut reader, re, s, group).map(Out::Int),
+            Search::Substr => find(re, s)
+                .map(|m| match m {
+                    Some(m) if !m.is_empty() => Out::Str(m),
+                    _ => Out::Null,
+                })
+                .map_err(match_error),
+        };
+        match res {
+            Ok(v) => out.push(v),
+            Err(err) => {
+                out.push(row_failure(cfg, i, pat, err).resolve(no_match(), Out::Null)?);
+            }
+        }
+    }
+
+    Ok(finish(search, strings, out))
+}
+
+fn finish<S: StrArray>(search: Search, strings: &S, out: Vec<Out<'_>>) -> ArrayRef {
+    let int = |o: Out<'_>| match o {
+        Out::Int(v) => Some(v),
+        _ => None,
+    };
+    match (search, strings.data_type()) {
+        (Search::Substr, _) => {
+            let mut b = S::builder_with_capacity(out.len(), out.len() * 4);
+            for o in out {
+                match o {
+                    Out::Str(v) => b.append_value(v),
+                    _ => b.append_null(),
+                }
+            }
+            b.finish_array()
+        }
+        (_, DataType::LargeUtf8) => {
+            Arc::new(out.into_iter().map(int).collect::<Int64Array>()) as ArrayRef
+        }
+        // Utf8 offsets are i32, so counts and positions always fit
+        _ => Arc::new(
+            out.into_iter()
+                .map(|o| int(o).map(|v| v as i32))
+                .collect::<Int32Array>(),
+        ) as ArrayRef,
+    }
+}
+
+/// Number of matches found by Java's `Matcher.find()` loop: after an empty match the next
+/// search starts one character later, after a non-empty one it starts at its end (where an
+/// empty match may then be reported).
+fn count_matches(re: &Regex, s: &str) -> Result<i64, Box<RegexError>> {
+    let mut count = 0;
+    let mut pos = 0;
+    while pos <= s.len() {
+        let Some((start, end)) = find_at(re, s, pos)? else {
+            break;
+        };
+        count += 1;
+        pos = if start == end {
+            end + s[end..].chars().next().map_or(1, char::len_utf8)
+        } else {
+            end
+        };
+    }
+    Ok(count)
+}
+
+/// 1-based UTF-16 position of group `group` in the first match; 0 when nothing matched or
+/// the group did not participate. A match with fewer than `group` groups is an error.
+fn group_position(
+    reader: &mut GroupReader,
+    re: &Regex,
+    s: &str,
+    group: i64,
+) -> Result<i64, RegexpExtractError> {
+    let match_error = |e: Box<RegexError>| RegexpExtractError::MatchError(e.to_string());
+    let start = if group == 0 {
+        find_at(re, s, 0)
+            .map_err(match_error)?
+            .map(|(start, _)| start)
+    } else {
+        let buf = reader.buf_for(re);
+        if !captures_read(re, buf, s).map_err(match_error)? {
+            return Ok(0);
+        }
+        let groups = re.captures_len() - 1;
+        if group as usize > groups {
+            return Err(RegexpExtractError::GroupIndexOutOfRange {
+                func: "regexp_instr",
+                idx: group,
+                groups,
+            });
+        }
+        buf.get(group as usize).map(|(start, _)| start)
+    };
+    Ok(start.map_or(0, |b| s[..b].encode_utf16().count() as i64 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::re::compile;
+
+    #[test]
+    fn count_follows_java_find_loop() {
+        let re = compile("a*").unwrap();
+        assert_eq!(count_matches(&re, "baaab").unwrap(), 4);
+        let re = compile("").unwrap();
+        assert_eq!(count_matches(&re, "añc").unwrap(), 4);
+        let re = compile(r"\d+").unwrap();
+        assert_eq!(count_matches(&re, "1 22 333").unwrap(), 3);
+        assert_eq!(count_matches(&re, "").unwrap(), 0);
+    }
+
+    #[test]
+    fn positions_are_utf16_based() {
+        let re = compile(r"(\d)").unwrap();
+        let r = &mut GroupReader::new();
+        assert_eq!(group_position(r, &re, "😀a1", 0).unwrap(), 4);
+        assert_eq!(group_position(r, &re, "東京1", 1).unwrap(), 3);
+        assert_eq!(group_position(r, &re, "abc", 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn group_index_is_checked_on_match() {
+        let re = compile(r"(\d)|(x)").unwrap();
+        let r = &mut GroupReader::new();
+        assert_eq!(group_position(r, &re, "a1", 2).unwrap(), 0);
+        assert_eq!(group_position(r, &re, "abc", 3).unwrap(), 0);
+        assert!(matches!(
+            group_position(r, &re, "a1", 3),
+            Err(RegexpExtractError::GroupIndexOutOfRange {
+                func: "regexp_instr",
+                idx: 3,
+                groups: 2
+            })
+        ));
+    }
+}
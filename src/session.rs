@@ -0,0 +1,222 @@
+//! Session options under the `regexp_extract.` prefix, e.g.
+//! `SET regexp_extract.invalid_pattern_mode = 'empty_string'`.
+//!
+//! DataFusion 49 does not hand session config to `invoke_with_args`, so the options are
+//! applied when a plan is analyzed: `SessionOptionsRule` (an analyzer rule) rebuilds every
+//! call to a UDF of this crate built from a `RegexpExtractConfig` (`regexp_extract`,
+//! `regexp_extract_span`, `rlike`, `regexp_count`/`regexp_instr`/`regexp_substr`, `split`,
+//! `regexp_classify`/`regexp_match_any`) with its config overridden by the options that
+//! are set; unset options keep the config the UDF was built with (including its
+//! `ErrorLog`). `regexp_classify`/`regexp_match_any` handle invalid rules when they are
+//! built, so for them `invalid_pattern_mode` only governs rows whose match fails.
+//!
+//! A query sees the values current when its plan is analyzed, i.e. when the SQL is planned
+//! or a `DataFrame` is created (`ctx.sql`, `ctx.table`, ...). A plan or `DataFrame`
+//! created before a `SET` keeps the values it was analyzed with, even when it is executed
+//! afterwards.
+//!
+//! `binary_output` and `span_byte_offsets` change the result type and stay per UDF.
+
+use crate::classify::RegexpClassifyUdf;
+use crate::config::{InvalidPatternMode, RegexpExtractConfig};
+use crate::rlike::RlikeUdf;
+use crate::search::RegexpSearchUdf;
+use crate::span::RegexpExtractSpanUdf;
+use crate::split::SplitUdf;
+use crate::udf::RegexpExtractUdf;
+use datafusion::common::config::{ConfigEntry, ConfigExtension, ConfigOptions, ExtensionOptions};
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{DataFusionError, Result};
+use datafusion::execution::context::SessionContext;
+use datafusion::logical_expr::expr::ScalarFunction;
+use datafusion::logical_expr::{Expr, LogicalPlan, ScalarUDF};
+use datafusion::optimizer::AnalyzerRule;
+use std::any::Any;
+use std::sync::Arc;
+
+/// `regexp_extract.*` session options; `None` keeps the UDF's own setting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegexpExtractOptions {
+    pub cache_size: Option<usize>,
+    pub invalid_pattern_mode: Option<InvalidPatternMode>,
+    pub parallel_threshold: Option<usize>,
+}
+
+impl RegexpExtractOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cache_size(mut self, n: usize) -> Self {
+        self.cache_size = Some(n);
+        self
+    }
+
+    pub fn invalid_pattern_mode(mut self, m: InvalidPatternMode) -> Self {
+        self.invalid_pattern_mode = Some(m);
+        self
+    }
+
+    pub fn parallel_threshold(mut self, rows: usize) -> Self {
+        self.parallel_threshold = Some(rows);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `base` with the set options applied.
+    pub fn apply(&self, base: &RegexpExtractConfig) -> RegexpExtractConfig {
+        let mut cfg = base.clone();
+        if let Some(n) = self.cache_size {
+            cfg.cache_size = n;
+        }
+        if let Some(m) = self.invalid_pattern_mode {
+            cfg.invalid_pattern_mode = m;
+        }
+        if let Some(rows) = self.parallel_threshold {
+            cfg.parallel_threshold = rows;
+        }
+        cfg
+    }
+}
+
+impl ConfigExtension for RegexpExtractOptions {
+    const PREFIX: &'static str = "regexp_extract";
+}
+
+impl ExtensionOptions for RegexpExtractOptions {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn cloned(&self) -> Box<dyn ExtensionOptions> {
+        Box::new(self.clone())
+    }
+
+    /// Values are parsed like `RegexpExtractConfig::set`.
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let mut parsed = RegexpExtractConfig::default();
+        match key {
+            "cache_size" | "invalid_pattern_mode" | "parallel_threshold" => {
+                parsed.set(key, value).map_err(DataFusionError::from)?
+            }
+            _ => {
+                return Err(DataFusionError::Configuration(format!(
+                    "regexp_extract: `{}.{key}` is not a session option (expected \
+                     cache_size, invalid_pattern_mode or parallel_threshold)",
+                    Self::PREFIX
+                )));
+            }
+        }
+        match key {
+            "cache_size" => self.cache_size = Some(parsed.cache_size),
+            "invalid_pattern_mode" => self.invalid_pattern_mode = Some(parsed.invalid_pattern_mode),
+            _ => self.parallel_threshold = Some(parsed.parallel_threshold),
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Vec<ConfigEntry> {
+        let entry = |key: &str, value: Option<String>, description| ConfigEntry {
+            key: format!("{}.{key}", Self::PREFIX),
+            value,
+            description,
+        };
+        vec![
+            entry(
+                "cache_size",
+                self.cache_size.map(|n| n.to_string()),
+                "Capacity of the per-batch compiled-pattern cache",
+            ),
+            entry(
+                "invalid_pattern_mode",
+                self.invalid_pattern_mode.map(|m| m.to_string()),
+                "error, empty_string or collect",
+            ),
+            entry(
+                "parallel_threshold",
+                self.parallel_threshold.map(|n| n.to_string()),
+                "Rows from which a batch is split across the rayon pool",
+            ),
+        ]
+    }
+}
+
+/// A UDF of this crate built from a `RegexpExtractConfig`.
+pub(crate) trait Reconfigure {
+    fn config(&self) -> &RegexpExtractConfig;
+
+    /// The same function with `cfg` instead.
+    fn with_config(&self, cfg: RegexpExtractConfig) -> ScalarUDF;
+}
+
+/// `udf` as a [`Reconfigure`], or `None` when it is not one of this crate's UDFs.
+fn reconfigurable(udf: &ScalarUDF) -> Option<&dyn Reconfigure> {
+    let any = udf.inner().as_any();
+    fn cast<T: Reconfigure + 'static>(any: &dyn Any) -> Option<&dyn Reconfigure> {
+        any.downcast_ref::<T>().map(|u| u as &dyn Reconfigure)
+    }
+    cast::<RegexpExtractUdf>(any)
+        .or_else(|| cast::<RegexpExtractSpanUdf>(any))
+        .or_else(|| cast::<RlikeUdf>(any))
+        .or_else(|| cast::<RegexpSearchUdf>(any))
+        .or_else(|| cast::<SplitUdf>(any))
+        .or_else(|| cast::<RegexpClassifyUdf>(any))
+}
+
+/// Analyzer rule applying the session's `RegexpExtractOptions` to calls of this crate's
+/// UDFs.
+#[derive(Debug, Default)]
+pub struct SessionOptionsRule;
+
+impl AnalyzerRule for SessionOptionsRule {
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        let Some(opts) = config.extensions.get::<RegexpExtractOptions>() else {
+            return Ok(plan);
+        };
+        if opts.is_empty() {
+            return Ok(plan);
+        }
+        plan.transform_up_with_subqueries(|plan| {
+            plan.map_expressions(|e| e.transform_up(|e| rewrite(e, opts)))
+        })
+        .map(|t| t.data)
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_session_options"
+    }
+}
+
+fn rewrite(e: Expr, opts: &RegexpExtractOptions) -> Result<Transformed<Expr>> {
+    let Expr::ScalarFunction(f) = e else {
+        return Ok(Transformed::no(e));
+    };
+    let Some(udf) = reconfigurable(&f.func) else {
+        return Ok(Transformed::no(Expr::ScalarFunction(f)));
+    };
+    let udf = Arc::new(udf.with_config(opts.apply(udf.config())));
+    Ok(Transformed::yes(Expr::ScalarFunction(
+        ScalarFunction::new_udf(udf, f.args),
+    )))
+}
+
+/// Registers `RegexpExtractOptions` (unless the session config already has them) and
+/// `SessionOptionsRule` with `ctx`, so `SET regexp_extract.*` takes effect. Call it once.
+pub fn enable_session_options(ctx: &SessionContext) {
+    {
+        let state = ctx.state_ref();
+        let mut state = state.write();
+        let options = state.config_mut().options_mut();
+        if options.extensions.get::<RegexpExtractOptions>().is_none() {
+            options.extensions.insert(RegexpExtractOptions::new());
+        }
+    }
+    ctx.add_analyzer_rule(Arc::new(SessionOptionsRule));
+}
@@ -0,0 +1,195 @@
+//! `regexp_extract_span(str, pattern, idx)`: where the selected group matched.
+//!
+//! Takes the same arguments and follows the same rules as `regexp_extract`, but returns
+//! `Struct{value, start, end}`: `value` is what `regexp_extract` would return and
+//! `start`/`end` are 0-based, end-exclusive offsets of the group in `str`, counted in
+//! UTF-16 code units like Java string indices, so a character outside the BMP counts
+//! twice; `start + 1` is what `regexp_instr` returns for the same group. With
+//! `RegexpExtractConfig::span_byte_offsets` the struct also has `byte_start`/`byte_end`.
+//! Without a match `value` is `""` and the offsets are NULL; a NULL input gives a NULL
+//! struct.
+
+use crate::config::RegexpExtractConfig;
+use crate::eval::evaluate_regexp_extract_span_with;
+use crate::kernel::{RowSink, StrArray, StrBuilder};
+use crate::session::Reconfigure;
+use datafusion::arrow::array::{ArrayRef, Int64Builder, StructArray};
+use datafusion::arrow::buffer::NullBuffer;
+use datafusion::arrow::datatypes::{DataType, Field, Fields};
+use datafusion::common::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+pub fn regexp_extract_span_udf_with(cfg: RegexpExtractConfig) -> ScalarUDF {
+    ScalarUDF::from(RegexpExtractSpanUdf::new_with(cfg))
+}
+
+pub fn regexp_extract_span_udf() -> ScalarUDF {
+    regexp_extract_span_udf_with(RegexpExtractConfig::default())
+}
+
+/// Struct fields of the result for a `value` of type `dt`.
+pub fn span_fields(dt: &DataType, byte_offsets: bool) -> Fields {
+    let mut fields = vec![
+        Field::new("value", dt.clone(), true),
+        Field::new("start", DataType::Int64, true),
+        Field::new("end", DataType::Int64, true),
+    ];
+    if byte_offsets {
+        fields.push(Field::new("byte_start", DataType::Int64, true));
+        fields.push(Field::new("byte_end", DataType::Int64, true));
+    }
+    fields.into()
+}
+
+/// Internal implementation of the `regexp_extract_span` UDF
+#[derive(Debug)]
+pub(crate) struct RegexpExtractSpanUdf {
+    signature: Signature,
+    cfg: Arc<RegexpExtractConfig>,
+}
+
+impl RegexpExtractSpanUdf {
+    fn new_with(cfg: RegexpExtractConfig) -> Self {
+        // Accept (Utf8|LargeUtf8, Utf8|LargeUtf8, Int32|Int64), as regexp_extract
+        let strs = [DataType::Utf8, DataType::LargeUtf8];
+        let mut sigs = Vec::new();
+        for s in &strs {
+            for p in &strs {
+                for i in [DataType::Int32, DataType::Int64] {
+                    sigs.push(TypeSignature::Exact(vec![s.clone(), p.clone(), i]));
+                }
+            }
+        }
+
+        Self {
+            signature: Signature::one_of(sigs, Volatility::Immutable),
+            cfg: Arc::new(cfg),
+        }
+    }
+}
+
+impl Reconfigure for RegexpExtractSpanUdf {
+    fn config(&self) -> &RegexpExtractConfig {
+        &self.cfg
+    }
+
+    fn with_config(&self, cfg: RegexpExtractConfig) -> ScalarUDF {
+        ScalarUDF::from(Self::new_with(cfg))
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractSpanUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_span"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match arg_types.first() {
+            Some(dt @ (DataType::Utf8 | DataType::LargeUtf8)) => Ok(DataType::Struct(span_fields(
+                dt,
+                self.cfg.span_byte_offsets,
+            ))),
+            other => Err(DataFusionError::Plan(format!(
+                "regexp_extract_span expects first argument Utf8 or LargeUtf8, got: {other:?}"
+            ))),
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        evaluate_regexp_extract_span_with(args, self.cfg.as_ref())
+    }
+}
+
+/// Kernel output for `regexp_extract_span`: one struct row per input row.
+pub(crate) struct SpanSink<B> {
+    values: B,
+    start: Int64Builder,
+    end: Int64Builder,
+    bytes: Option<(Int64Builder, Int64Builder)>,
+    valid: Vec<bool>,
+}
+
+impl<S: StrArray> RowSink<S> for SpanSink<S::Builder> {
+    fn with_capacity(rows: usize, bytes: usize, cfg: &RegexpExtractConfig) -> Self {
+        Self {
+            values: S::builder_with_capacity(rows, bytes),
+            start: Int64Builder::with_capacity(rows),
+            end: Int64Builder::with_capacity(rows),
+            bytes: cfg.span_byte_offsets.then(|| {
+                (
+                    Int64Builder::with_capacity(rows),
+                    Int64Builder::with_capacity(rows),
+                )
+            }),
+            valid: Vec::with_capacity(rows),
+        }
+    }
+
+    fn append_null(&mut self) {
+        self.values.append_null();
+        self.start.append_null();
+        self.end.append_null();
+        if let Some((bs, be)) = &mut self.bytes {
+            bs.append_null();
+            be.append_null();
+        }
+        self.valid.push(false);
+    }
+
+    fn append_match(&mut self, s: &str, span: Option<(usize, usize)>) {
+        self.valid.push(true);
+        let Some((a, b)) = span else {
+            self.values.append_value("");
+            self.start.append_null();
+            self.end.append_null();
+            if let Some((bs, be)) = &mut self.bytes {
+                bs.append_null();
+                be.append_null();
+            }
+            return;
+        };
+
+        let value = &s[a..b];
+        let start = s[..a].encode_utf16().count();
+        self.values.append_value(value);
+        self.start.append_value(start as i64);
+        self.end
+            .append_value((start + value.encode_utf16().count()) as i64);
+        if let Some((bs, be)) = &mut self.bytes {
+            bs.append_value(a as i64);
+            be.append_value(b as i64);
+        }
+    }
+
+    fn finish_array(mut self) -> ArrayRef {
+        let values = self.values.finish_array();
+        let fields = span_fields(values.data_type(), self.bytes.is_some());
+        let mut columns = vec![
+            values,
+            Arc::new(self.start.finish()) as ArrayRef,
+            Arc::new(self.end.finish()) as ArrayRef,
+        ];
+        if let Some((mut bs, mut be)) = self.bytes {
+            columns.push(Arc::new(bs.finish()));
+            columns.push(Arc::new(be.finish()));
+        }
+        Arc::new(StructArray::new(
+            fields,
+            columns,
+            Some(NullBuffer::from(self.valid)),
+        ))
+    }
+}
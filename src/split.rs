@@ -0,0 +1,355 @@
+//! Spark `split(str, regex[, limit])`: splits around matches of a Java-style regex.
+//!
+//! Semantics follow Spark's `UTF8String.split`, which delegates to Java's `String.split`:
+//! - `limit > 0`: at most `limit` elements; the last one holds the unsplit remainder.
+//! - `limit <= 0` (default `-1`): split as often as possible. Spark turns `0` into `-1`, so
+//!   trailing empty strings are kept either way (unlike Java's own `split(regex, 0)`).
+//! - An empty match at the very start never produces a leading empty element, and an empty
+//!   match is also reported right after a non-empty one (Java's `Matcher.find()` loop).
+//! - An empty pattern splits a non-empty string into its characters.
+//!
+//! The result is `List<Utf8>` for `Utf8` input and `List<LargeUtf8>` for `LargeUtf8`.
+//! NULL inputs give NULL; in `InvalidPatternMode::EmptyString` an invalid pattern behaves
+//! as "no match" (`[str]`), in `Collect` the row is NULL.
+
+use crate::config::RegexpExtractConfig;
+use crate::error::RegexpExtractError;
+use crate::kernel::{ScalarPattern, StrArray, StrBuilder, check_lengths, in_batch, row_failure};
+use crate::pattern_cache::PatternCache;
+use crate::re::{Regex, RegexError, find_at};
+use crate::session::Reconfigure;
+use crate::types::to_array;
+use datafusion::arrow::array::{Array, ArrayBuilder, ArrayRef, Int64Array, ListBuilder};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::{as_int64_array, as_large_string_array, as_string_array};
+use datafusion::common::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+pub fn split_udf_with(cfg: RegexpExtractConfig) -> ScalarUDF {
+    ScalarUDF::from(SplitUdf::new_with(cfg))
+}
+
+pub fn split_udf() -> ScalarUDF {
+    split_udf_with(RegexpExtractConfig::default())
+}
+
+/// Internal implementation of `split`
+#[derive(Debug)]
+pub(crate) struct SplitUdf {
+    signature: Signature,
+    cfg: Arc<RegexpExtractConfig>,
+}
+
+impl SplitUdf {
+    fn new_with(cfg: RegexpExtractConfig) -> Self {
+        // Accept (Utf8|LargeUtf8, Utf8|LargeUtf8[, Int32|Int64])
+        let strs = [DataType::Utf8, DataType::LargeUtf8];
+        let mut sigs = Vec::new();
+        for s in &strs {
+            for p in &strs {
+                sigs.push(TypeSignature::Exact(vec![s.clone(), p.clone()]));
+                for l in [DataType::Int32, DataType::Int64] {
+                    sigs.push(TypeSignature::Exact(vec![s.clone(), p.clone(), l]));
+                }
+            }
+        }
+
+        Self {
+            signature: Signature::one_of(sigs, Volatility::Immutable),
+            cfg: Arc::new(cfg),
+        }
+    }
+}
+
+impl Reconfigure for SplitUdf {
+    fn config(&self) -> &RegexpExtractConfig {
+        &self.cfg
+    }
+
+    fn with_config(&self, cfg: RegexpExtractConfig) -> ScalarUDF {
+        ScalarUDF::from(Self::new_with(cfg))
+    }
+}
+
+impl ScalarUDFImpl for SplitUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "split"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match arg_types.first() {
+            Some(dt @ (DataType::Utf8 | DataType::LargeUtf8)) => {
+                Ok(DataType::List(list_item_field(dt)))
+            }
+            other => Err(DataFusionError::Plan(format!(
+                "split expects first argument Utf8 or LargeUtf8, got: {other:?}"
+            ))),
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        evaluate_split_with(args, self.cfg.as_ref())
+    }
+}
+
+fn list_item_field(dt: &DataType) -> Arc<Field> {
+    Arc::new(Field::new_list_field(dt.clone(), true))
+}
+
+pub fn evaluate_split_with(
+    args: ScalarFunctionArgs,
+    cfg: &RegexpExtractConfig,
+) -> Result<ColumnarValue> {
+    in_batch(cfg, |cfg| evaluate(args, cfg))
+}
+
+fn evaluate(args: ScalarFunctionArgs, cfg: &RegexpExtractConfig) -> Result<ColumnarValue> {
+    let rows = args.number_rows;
+    let a = &args.args;
+
+    if a.len() != 2 && a.len() != 3 {
+        return Err(DataFusionError::Execution(format!(
+            "split expects 2 or 3 arguments, got {}",
+            a.len()
+        )));
+    }
+
+    let s_arr = to_array(&a[0], rows)?;
+    // keep scalar pattern/limit as length-1 arrays so they are handled once
+    let p_arr = match &a[1] {
+        ColumnarValue::Scalar(sv) => sv.to_array()?,
+        _ => to_array(&a[1], rows)?,
+    };
+    let l_arr = match a.get(2) {
+        Some(ColumnarValue::Scalar(sv)) => sv.to_array()?,
+        Some(cv) => to_array(cv, rows)?,
+        None => Arc::new(Int64Array::from(vec![-1_i64])) as ArrayRef,
+    };
+    let l_arr = match l_arr.data_type() {
+        DataType::Int32 | DataType::Int64 => cast(&l_arr, &DataType::Int64)?,
+        other => {
+            return Err(DataFusionError::Execution(format!(
+                "split limit must be Int32 or Int64, got {other:?}"
+            )));
+        }
+    };
+    check_lengths(
+        s_arr.len(),
+        &[("pattern", p_arr.len()), ("limit", l_arr.len())],
+    )?;
+    let limits = as_int64_array(&l_arr)?;
+
+    let out = match (s_arr.data_type(), p_arr.data_type()) {
+        (DataType::Utf8, DataType::Utf8) => run_split(
+            as_string_array(&s_arr)?,
+            as_string_array(&p_arr)?,
+            limits,
+            cfg,
+        ),
+        (DataType::Utf8, DataType::LargeUtf8) => run_split(
+            as_string_array(&s_arr)?,
+            as_large_string_array(&p_arr)?,
+            limits,
+            cfg,
+        ),
+        (DataType::LargeUtf8, DataType::Utf8) => run_split(
+            as_large_string_array(&s_arr)?,
+            as_string_array(&p_arr)?,
+            limits,
+            cfg,
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => run_split(
+            as_large_string_array(&s_arr)?,
+            as_large_string_array(&p_arr)?,
+            limits,
+            cfg,
+        ),
+        (s_dt, p_dt) => {
+            return Err(DataFusionError::Execution(format!(
+                "split expects Utf8 or LargeUtf8 arguments, got ({s_dt:?}, {p_dt:?})"
+            )));
+        }
+    }
+    .map_err(DataFusionError::from)?;
+
+    Ok(ColumnarValue::Array(out))
+}
+
+fn run_split<S, P>(
+    strings: &S,
+    patterns: &P,
+    limits: &Int64Array,
+    cfg: &RegexpExtractConfig,
+) -> Result<ArrayRef, RegexpExtractError>
+where
+    S: StrArray,
+    S::Builder: ArrayBuilder,
+    P: StrArray,
+{
+    let n = strings.len();
+    let mut cache = PatternCache::new(cfg.cache_size).with_shared(cfg.pattern_cache.clone());
+    let mut b = ListBuilder::with_capacity(S::builder_with_capacity(n, n * 16), n)
+        .with_field(list_item_field(strings.data_type()));
+    let mut parts: Vec<&str> = Vec::new();
+
+    let pat_scalar = patterns.len() == 1;
+    let scalar = if pat_scalar && !patterns.is_null(0) && !patterns.value(0).is_empty() {
+        Some(ScalarPattern::<Regex>::compile(patterns.value(0), cfg)?)
+    } else {
+        None
+    };
+
+    for i in 0..n {
+        let p = if pat_scalar { 0 } else { i };
+        let l = if limits.len() == 1 { 0 } else { i };
+        if strings.is_null(i) || patterns.is_null(p) || limits.is_null(l) {
+            b.append_null();
+            continue;
+        }
+        let s = strings.value(i);
+        let limit = limits.value(l);
+
+        parts.clear();
+        let pat = patterns.value(p);
+        let failure = if pat.is_empty() {
+            split_chars(s, limit, &mut parts);
+            None
+        } else {
+            let re = match &scalar {
+                Some(scalar) => scalar.regex().ok_or_else(|| scalar.failure(i, cfg)),
+                None => cache
+                    .get_or_compile(pat)
+                    .map_err(|e| row_failure(cfg, i, pat, e)),
+            };
+            match re {
+                Ok(re) => split_regex(re, s, limit, &mut parts).err().map(|e| {
+                    let err = RegexpExtractError::MatchError(e.to_string());
+                    row_failure(cfg, i, pat, err)
+                }),
+                Err(f) => Some(f),
+            }
+        };
+        if let Some(f) = failure {
+            parts.clear();
+            // "no match" is the whole string
+            if !f.resolve(true, false)? {
+                b.append_null();
+                continue;
+            }
+            parts.push(s);
+        }
+
+        for part in &parts {
+            b.values().append_value(part);
+        }
+        b.append(true);
+    }
+
+    Ok(Arc::new(b.finish()))
+}
+
+/// Java's `Pattern.split(input, limit)` for `limit != 0`.
+fn split_regex<'h>(
+    re: &Regex,
+    s: &'h str,
+    limit: i64,
+    parts: &mut Vec<&'h str>,
+) -> Result<(), Box<RegexError>> {
+    let limited = limit > 0;
+    let mut index = 0;
+    let mut pos = 0;
+    while pos <= s.len() {
+        if limited && parts.len() as i64 >= limit - 1 {
+            break;
+        }
+        let Some((start, end)) = find_at(re, s, pos)? else {
+            break;
+        };
+        pos = if start == end {
+            end + s[end..].chars().next().map_or(1, char::len_utf8)
+        } else {
+            end
+        };
+        // a zero-width match at the beginning never yields a leading empty string
+        if start == 0 && end == 0 {
+            continue;
+        }
+        parts.push(&s[index..start]);
+        index = end;
+    }
+    parts.push(&s[index..]);
+    Ok(())
+}
+
+/// Spark's special case for an empty pattern: one element per character, the last one
+/// holding the remainder when `limit` is smaller than the character count.
+fn split_chars<'h>(s: &'h str, limit: i64, parts: &mut Vec<&'h str>) {
+    if s.is_empty() {
+        parts.push(s);
+        return;
+    }
+    let max = if limit > 0 {
+        limit as usize
+    } else {
+        usize::MAX
+    };
+    let mut it = s.char_indices().peekable();
+    while let Some((start, c)) = it.next() {
+        if parts.len() + 1 == max || it.peek().is_none() {
+            parts.push(&s[start..]);
+            break;
+        }
+        parts.push(&s[start..start + c.len_utf8()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::re::compile;
+
+    fn split(pat: &str, s: &str, limit: i64) -> Vec<String> {
+        let mut parts = Vec::new();
+        if pat.is_empty() {
+            split_chars(s, limit, &mut parts);
+        } else {
+            split_regex(&compile(pat).unwrap(), s, limit, &mut parts).unwrap();
+        }
+        parts.into_iter().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn follows_java_pattern_split() {
+        assert_eq!(split("[1-9]+", "oneAtwoBthreeC", -1), ["oneAtwoBthreeC"]);
+        assert_eq!(split(",", "a,b,,c,,", -1), ["a", "b", "", "c", "", ""]);
+        assert_eq!(split(",", "a,b,,c,,", 2), ["a", "b,,c,,"]);
+        assert_eq!(split(",", "a,b", 1), ["a,b"]);
+        assert_eq!(split(",", ",a", -1), ["", "a"]);
+        assert_eq!(split(",", "", -1), [""]);
+        // empty matches: none leading, one right after a non-empty match
+        assert_eq!(split("a*", "baaab", -1), ["b", "", "b", ""]);
+        assert_eq!(split("x*", "ab", -1), ["a", "b", ""]);
+    }
+
+    #[test]
+    fn empty_pattern_splits_characters() {
+        assert_eq!(split("", "añ😀", -1), ["a", "ñ", "😀"]);
+        assert_eq!(split("", "abcd", 2), ["a", "bcd"]);
+        assert_eq!(split("", "ab", 5), ["a", "b"]);
+        assert_eq!(split("", "", -1), [""]);
+    }
+}
@@ -0,0 +1,436 @@
+//! Substrait mapping for `regexp_extract`: the extension the function is declared in and its
+//! config as function options.
+//!
+//! The function is declared in `extensions/functions_regexp_extract.yaml`; plans reference it
+//! as `EXTENSION_URI` + `FUNCTION_NAME`. `invalid_pattern_mode` travels as a
+//! `FunctionOption` (name + ordered preferences, as in Substrait's message), so a producer
+//! emits `function_options(cfg)` next to the function reference and a consumer rebuilds the
+//! UDF with `udf_from_options`. Substrait options take a fixed set of values, so
+//! `cache_size`, which only affects performance, is left to the consumer's config like the
+//! other fields; a decoded `Collect` config has no `ErrorLog`, so its rows are nulled but
+//! not recorded.
+//!
+//! The binary impls declare a binary result, which is what `BinaryOutput::Binary` returns;
+//! `binary_output` is not an option, so a plan over binary input only round-trips with it.
+//!
+//! With the `substrait` feature, `to_substrait_plan`/`from_substrait_plan` do this inside
+//! `datafusion-substrait`: the same conversions with a producer that writes the options and
+//! a consumer that applies them to the session's registered `regexp_extract` config.
+
+use crate::config::RegexpExtractConfig;
+use crate::error::RegexpExtractError;
+use crate::udf::regexp_extract_udf_with;
+use datafusion::logical_expr::ScalarUDF;
+
+pub const EXTENSION_URI: &str = "https://github.com/0xdmtry/datafusion-regexp-extract-udf/blob/main/extensions/functions_regexp_extract.yaml";
+
+pub const FUNCTION_NAME: &str = "regexp_extract";
+
+/// Option names understood by `config_from_options`.
+pub const OPTION_NAMES: [&str; 1] = ["invalid_pattern_mode"];
+
+/// Substrait `FunctionOption`: the first preference the consumer supports wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionOption {
+    pub name: String,
+    pub preference: Vec<String>,
+}
+
+impl FunctionOption {
+    pub fn new(name: &str, value: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            preference: vec![value.to_string()],
+        }
+    }
+}
+
+/// `cfg` as function options, in `OPTION_NAMES` order.
+pub fn function_options(cfg: &RegexpExtractConfig) -> Vec<FunctionOption> {
+    vec![FunctionOption::new(
+        "invalid_pattern_mode",
+        cfg.invalid_pattern_mode,
+    )]
+}
+
+/// Rebuilds a config from function options; missing options keep their defaults.
+/// Each option takes its first preference that parses; unknown names are rejected.
+pub fn config_from_options(
+    options: &[FunctionOption],
+) -> Result<RegexpExtractConfig, RegexpExtractError> {
+    apply_options(RegexpExtractConfig::default(), options)
+}
+
+/// `config_from_options` on top of `cfg` instead of the defaults.
+pub fn apply_options(
+    mut cfg: RegexpExtractConfig,
+    options: &[FunctionOption],
+) -> Result<RegexpExtractConfig, RegexpExtractError> {
+    for opt in options {
+        if !OPTION_NAMES.contains(&opt.name.as_str()) {
+            return Err(RegexpExtractError::InvalidConfig {
+                key: opt.name.clone(),
+                message: "unknown function option".to_string(),
+            });
+        }
+        let mut last = RegexpExtractError::InvalidConfig {
+            key: opt.name.clone(),
+            message: "no preference given".to_string(),
+        };
+        let chosen = opt.preference.iter().any(|v| match cfg.set(&opt.name, v) {
+            Ok(()) => true,
+            Err(e) => {
+                last = e;
+                false
+            }
+        });
+        if !chosen {
+            return Err(last);
+        }
+    }
+    Ok(cfg)
+}
+
+/// Function options for a `regexp_extract` UDF, or `None` for any other function.
+pub fn udf_options(udf: &ScalarUDF) -> Option<Vec<FunctionOption>> {
+    crate::udf::regexp_extract_config(udf).map(function_options)
+}
+
+/// The `regexp_extract` UDF described by `options`.
+pub fn udf_from_options(options: &[FunctionOption]) -> Result<ScalarUDF, RegexpExtractError> {
+    config_from_options(options).map(regexp_extract_udf_with)
+}
+
+#[cfg(feature = "substrait")]
+pub use plan::{
+    RegexpExtractConsumer, RegexpExtractProducer, from_substrait_plan, to_substrait_plan,
+};
+
+/// `datafusion-substrait` producer/consumer that carry `regexp_extract` options.
+#[cfg(feature = "substrait")]
+mod plan {
+    use super::{EXTENSION_URI, FUNCTION_NAME, FunctionOption, apply_options};
+    use crate::udf::{regexp_extract_config, regexp_extract_udf_with};
+    use async_trait::async_trait;
+    use datafusion::catalog::TableProvider;
+    use datafusion::common::{DFSchema, DFSchemaRef, Result, TableReference, not_impl_err};
+    use datafusion::common::{plan_err, substrait_err};
+    use datafusion::execution::registry::SerializerRegistry;
+    use datafusion::execution::{FunctionRegistry, SessionState};
+    use datafusion::logical_expr::{Expr, Extension, LogicalPlan, expr};
+    use datafusion_substrait::extensions::Extensions;
+    use datafusion_substrait::logical_plan::consumer::{
+        self, SubstraitConsumer, from_substrait_func_args, from_substrait_plan_with_consumer,
+        substrait_fun_name,
+    };
+    use datafusion_substrait::logical_plan::producer::{self, SubstraitProducer};
+    use datafusion_substrait::substrait::proto;
+    use datafusion_substrait::substrait::proto::expression::{RexType, ScalarFunction};
+    use datafusion_substrait::substrait::proto::extensions::SimpleExtensionUri;
+    use datafusion_substrait::substrait::proto::extensions::simple_extension_declaration::MappingType;
+    use datafusion_substrait::substrait::proto::rel::RelType;
+    use datafusion_substrait::substrait::proto::{
+        Expression, ExtensionLeafRel, ExtensionMultiRel, ExtensionSingleRel, Plan, Rel, plan_rel,
+    };
+    use std::sync::Arc;
+
+    /// Anchor of `EXTENSION_URI` in produced plans.
+    const EXTENSION_URI_ANCHOR: u32 = 1;
+
+    impl From<FunctionOption> for proto::FunctionOption {
+        fn from(o: FunctionOption) -> Self {
+            Self {
+                name: o.name,
+                preference: o.preference,
+            }
+        }
+    }
+
+    impl From<&proto::FunctionOption> for FunctionOption {
+        fn from(o: &proto::FunctionOption) -> Self {
+            Self {
+                name: o.name.clone(),
+                preference: o.preference.clone(),
+            }
+        }
+    }
+
+    /// `datafusion_substrait`'s `to_substrait_plan`, with `regexp_extract` calls carrying their
+    /// config as function options and declared under `EXTENSION_URI`.
+    pub fn to_substrait_plan(plan: &LogicalPlan, state: &SessionState) -> Result<Box<Plan>> {
+        // the default producer names the root's columns (its helper for that is private);
+        // the relation is produced again so regexp_extract calls get their options
+        let mut out = producer::to_substrait_plan(plan, state)?;
+        let mut producer = RegexpExtractProducer::new(state);
+        let rel = producer.handle_plan(plan)?;
+        match out.relations.first_mut().and_then(|r| r.rel_type.as_mut()) {
+            Some(plan_rel::RelType::Root(root)) => root.input = Some(*rel),
+            _ => return substrait_err!("expected a root relation"),
+        }
+
+        out.extensions = producer.get_extensions().into();
+        let mut declared = false;
+        for ext in &mut out.extensions {
+            if let Some(MappingType::ExtensionFunction(f)) = &mut ext.mapping_type {
+                if substrait_fun_name(&f.name) == FUNCTION_NAME {
+                    f.extension_uri_reference = EXTENSION_URI_ANCHOR;
+                    declared = true;
+                }
+            }
+        }
+        if declared {
+            out.extension_uris.push(SimpleExtensionUri {
+                extension_uri_anchor: EXTENSION_URI_ANCHOR,
+                uri: EXTENSION_URI.to_string(),
+            });
+        }
+        Ok(out)
+    }
+
+    /// `datafusion_substrait`'s `from_substrait_plan`, rebuilding `regexp_extract` calls from
+    /// their function options (see [`RegexpExtractConsumer`]).
+    pub async fn from_substrait_plan(state: &SessionState, plan: &Plan) -> Result<LogicalPlan> {
+        let extensions = Extensions::try_from(&plan.extensions)?;
+        if !extensions.type_variations.is_empty() {
+            return not_impl_err!("Type variation extensions are not supported");
+        }
+        let consumer = RegexpExtractConsumer::new(&extensions, state);
+        from_substrait_plan_with_consumer(&consumer, plan).await
+    }
+
+    /// Default producer behaviour, plus the config of each `regexp_extract` UDF as the
+    /// function options of its call.
+    pub struct RegexpExtractProducer<'a> {
+        extensions: Extensions,
+        serializer_registry: &'a dyn SerializerRegistry,
+    }
+
+    impl<'a> RegexpExtractProducer<'a> {
+        pub fn new(state: &'a SessionState) -> Self {
+            Self {
+                extensions: Extensions::default(),
+                serializer_registry: state.serializer_registry().as_ref(),
+            }
+        }
+    }
+
+    impl SubstraitProducer for RegexpExtractProducer<'_> {
+        fn register_function(&mut self, signature: String) -> u32 {
+            self.extensions.register_function(signature)
+        }
+
+        fn get_extensions(self) -> Extensions {
+            self.extensions
+        }
+
+        fn handle_scalar_function(
+            &mut self,
+            scalar_fn: &expr::ScalarFunction,
+            schema: &DFSchemaRef,
+        ) -> Result<Expression> {
+            let mut out = producer::from_scalar_function(self, scalar_fn, schema)?;
+            if let (Some(cfg), Some(RexType::ScalarFunction(f))) =
+                (regexp_extract_config(&scalar_fn.func), &mut out.rex_type)
+            {
+                f.options = super::function_options(cfg)
+                    .into_iter()
+                    .map(Into::into)
+                    .collect();
+            }
+            Ok(out)
+        }
+
+        // as the default producer, but with the inputs going through this one
+        fn handle_extension(&mut self, plan: &Extension) -> Result<Box<Rel>> {
+            let detail = pbjson_types::Any {
+                type_url: plan.node.name().to_string(),
+                value: self
+                    .serializer_registry
+                    .serialize_logical_plan(plan.node.as_ref())?
+                    .into(),
+            };
+            let mut inputs = plan
+                .node
+                .inputs()
+                .into_iter()
+                .map(|p| self.handle_plan(p))
+                .collect::<Result<Vec<_>>>()?;
+            let rel_type = match inputs.len() {
+                0 => RelType::ExtensionLeaf(ExtensionLeafRel {
+                    common: None,
+                    detail: Some(detail),
+                }),
+                1 => RelType::ExtensionSingle(Box::new(ExtensionSingleRel {
+                    common: None,
+                    detail: Some(detail),
+                    input: inputs.pop(),
+                })),
+                _ => RelType::ExtensionMulti(ExtensionMultiRel {
+                    common: None,
+                    detail: Some(detail),
+                    inputs: inputs.into_iter().map(|r| *r).collect(),
+                }),
+            };
+            Ok(Box::new(Rel {
+                rel_type: Some(rel_type),
+            }))
+        }
+    }
+
+    /// Default consumer behaviour, except that a `regexp_extract` call is rebuilt from its
+    /// function options on top of the config of the `regexp_extract` registered in the
+    /// session (so its `ErrorLog` and warmed patterns apply), or of the default config.
+    pub struct RegexpExtractConsumer<'a> {
+        extensions: &'a Extensions,
+        state: &'a SessionState,
+    }
+
+    impl<'a> RegexpExtractConsumer<'a> {
+        pub fn new(extensions: &'a Extensions, state: &'a SessionState) -> Self {
+            Self { extensions, state }
+        }
+
+        fn deserialize(&self, detail: Option<&pbjson_types::Any>) -> Result<Extension> {
+            let Some(detail) = detail else {
+                return substrait_err!("Unexpected empty detail in extension relation");
+            };
+            let node = self
+                .state
+                .serializer_registry()
+                .deserialize_logical_plan(&detail.type_url, &detail.value)?;
+            Ok(Extension { node })
+        }
+
+        async fn with_inputs(&self, ext: Extension, rels: &[Rel]) -> Result<LogicalPlan> {
+            let mut inputs = Vec::with_capacity(rels.len());
+            for rel in rels {
+                inputs.push(self.consume_rel(rel).await?);
+            }
+            let node = ext
+                .node
+                .with_exprs_and_inputs(ext.node.expressions(), inputs)?;
+            Ok(LogicalPlan::Extension(Extension { node }))
+        }
+    }
+
+    #[async_trait]
+    impl SubstraitConsumer for RegexpExtractConsumer<'_> {
+        async fn resolve_table_ref(
+            &self,
+            table_ref: &TableReference,
+        ) -> Result<Option<Arc<dyn TableProvider>>> {
+            let schema = self.state.schema_for_ref(table_ref.clone())?;
+            schema.table(table_ref.table()).await
+        }
+
+        fn get_extensions(&self) -> &Extensions {
+            self.extensions
+        }
+
+        fn get_function_registry(&self) -> &impl FunctionRegistry {
+            self.state
+        }
+
+        async fn consume_scalar_function(
+            &self,
+            f: &ScalarFunction,
+            input_schema: &DFSchema,
+        ) -> Result<Expr> {
+            let name = self.extensions.functions.get(&f.function_reference);
+            if name.map(|n| substrait_fun_name(n)) != Some(FUNCTION_NAME) {
+                return consumer::from_scalar_function(self, f, input_schema).await;
+            }
+            let base = match self.state.udf(FUNCTION_NAME) {
+                Ok(udf) => regexp_extract_config(&udf).cloned().unwrap_or_default(),
+                Err(_) => Default::default(),
+            };
+            let options = f
+                .options
+                .iter()
+                .map(FunctionOption::from)
+                .collect::<Vec<_>>();
+            let udf = regexp_extract_udf_with(apply_options(base, &options)?);
+            let args = from_substrait_func_args(self, &f.arguments, input_schema).await?;
+            Ok(udf.call(args))
+        }
+
+        async fn consume_extension_leaf(&self, rel: &ExtensionLeafRel) -> Result<LogicalPlan> {
+            Ok(LogicalPlan::Extension(
+                self.deserialize(rel.detail.as_ref())?,
+            ))
+        }
+
+        async fn consume_extension_single(&self, rel: &ExtensionSingleRel) -> Result<LogicalPlan> {
+            let ext = self.deserialize(rel.detail.as_ref())?;
+            let Some(input) = &rel.input else {
+                return plan_err!("ExtensionSingleRel missing input rel");
+            };
+            self.with_inputs(ext, std::slice::from_ref(input)).await
+        }
+
+        async fn consume_extension_multi(&self, rel: &ExtensionMultiRel) -> Result<LogicalPlan> {
+            let ext = self.deserialize(rel.detail.as_ref())?;
+            self.with_inputs(ext, &rel.inputs).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InvalidPatternMode;
+    use crate::udf::regexp_extract_config;
+
+    #[test]
+    fn options_round_trip_through_the_udf() {
+        let cfg = RegexpExtractConfig::new()
+            .cache_size(256)
+            .invalid_pattern_mode(InvalidPatternMode::EmptyString);
+        let opts = udf_options(&regexp_extract_udf_with(cfg)).unwrap();
+        assert_eq!(
+            opts,
+            [FunctionOption::new("invalid_pattern_mode", "empty_string")]
+        );
+
+        let udf = udf_from_options(&opts).unwrap();
+        assert_eq!(udf.name(), FUNCTION_NAME);
+        let back = regexp_extract_config(&udf).unwrap();
+        // performance-only, so not carried
+        assert_eq!(back.cache_size, 64);
+        assert_eq!(back.invalid_pattern_mode, InvalidPatternMode::EmptyString);
+
+        assert!(udf_options(&crate::rlike_udf()).is_none());
+    }
+
+    #[test]
+    fn first_supported_preference_wins() {
+        let opts = [FunctionOption {
+            name: "invalid_pattern_mode".to_string(),
+            preference: vec!["skip".to_string(), "COLLECT".to_string()],
+        }];
+        let cfg = config_from_options(&opts).unwrap();
+        assert_eq!(cfg.invalid_pattern_mode, InvalidPatternMode::Collect);
+        assert_eq!(
+            config_from_options(&[]).unwrap().invalid_pattern_mode,
+            InvalidPatternMode::Error
+        );
+    }
+
+    #[test]
+    fn bad_options_are_rejected() {
+        let err = config_from_options(&[FunctionOption::new("invalid_pattern_mode", "skip")])
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("regexp_extract: invalid config `invalid_pattern_mode`: got `skip`")
+        );
+        let none = FunctionOption {
+            name: "invalid_pattern_mode".to_string(),
+            preference: vec![],
+        };
+        assert!(config_from_options(&[none]).is_err());
+        // only the documented option travels in plans
+        assert!(config_from_options(&[FunctionOption::new("span_byte_offsets", true)]).is_err());
+        assert!(config_from_options(&[FunctionOption::new("cache_size", 8)]).is_err());
+    }
+}
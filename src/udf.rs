@@ -1,7 +1,9 @@
 //! UDF construction: logical surface only
 
+use crate::binary::{binary_return_type, is_binary};
 use crate::config::RegexpExtractConfig;
 use crate::eval::evaluate_regexp_extract_with;
+use crate::session::Reconfigure;
 use datafusion::arrow::datatypes::DataType;
 use datafusion::common::{DataFusionError, Result};
 use datafusion::logical_expr::{
@@ -19,9 +21,17 @@ pub fn regexp_extract_udf() -> ScalarUDF {
     ScalarUDF::from(RegexpExtractUdf::new())
 }
 
+/// The config a `regexp_extract` UDF was built with, or `None` for any other function.
+pub fn regexp_extract_config(udf: &ScalarUDF) -> Option<&RegexpExtractConfig> {
+    udf.inner()
+        .as_any()
+        .downcast_ref::<RegexpExtractUdf>()
+        .map(|f| f.cfg.as_ref())
+}
+
 /// Internal implementation of the `regexp_extract` UDF
 #[derive(Debug)]
-struct RegexpExtractUdf {
+pub(crate) struct RegexpExtractUdf {
     signature: Signature,
     cfg: Arc<RegexpExtractConfig>,
 }
@@ -31,7 +41,19 @@ impl RegexpExtractUdf {
         Self::new_with(RegexpExtractConfig::default())
     }
     fn new_with(cfg: RegexpExtractConfig) -> Self {
-        // Accept (Utf8|LargeUtf8, Utf8|LargeUtf8, Int32|Int64)
+        // Accept (Utf8|LargeUtf8|Binary|LargeBinary|BinaryView, Utf8|LargeUtf8, Int32|Int64)
+        let mut binary_combos = Vec::new();
+        for b in [
+            DataType::Binary,
+            DataType::LargeBinary,
+            DataType::BinaryView,
+        ] {
+            for p in [DataType::Utf8, DataType::LargeUtf8] {
+                for i in [DataType::Int32, DataType::Int64] {
+                    binary_combos.push((b.clone(), p.clone(), i));
+                }
+            }
+        }
         let combos = [
             (DataType::Utf8, DataType::Utf8, DataType::Int32),
             (DataType::Utf8, DataType::Utf8, DataType::Int64),
@@ -46,6 +68,7 @@ impl RegexpExtractUdf {
         let sig = Signature::one_of(
             combos
                 .into_iter()
+                .chain(binary_combos)
                 .map(|(a, b, c)| TypeSignature::Exact(vec![a, b, c]))
                 .collect(),
             Volatility::Immutable,
@@ -58,6 +81,16 @@ impl RegexpExtractUdf {
     }
 }
 
+impl Reconfigure for RegexpExtractUdf {
+    fn config(&self) -> &RegexpExtractConfig {
+        &self.cfg
+    }
+
+    fn with_config(&self, cfg: RegexpExtractConfig) -> ScalarUDF {
+        ScalarUDF::from(Self::new_with(cfg))
+    }
+}
+
 impl ScalarUDFImpl for RegexpExtractUdf {
     fn as_any(&self) -> &dyn Any {
         self
@@ -75,8 +108,11 @@ impl ScalarUDFImpl for RegexpExtractUdf {
         match arg_types.first() {
             Some(DataType::Utf8) => Ok(DataType::Utf8),
             Some(DataType::LargeUtf8) => Ok(DataType::LargeUtf8),
+            Some(dt) if is_binary(dt) => {
+                Ok(binary_return_type(dt, self.cfg.binary_output).expect("binary input type"))
+            }
             other => Err(DataFusionError::Plan(format!(
-                "regexp_extract expects first argument Utf8 or LargeUtf8, got: {other:?}"
+                "regexp_extract expects first argument Utf8, LargeUtf8 or binary, got: {other:?}"
             ))),
         }
     }
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, BinaryArray, BinaryViewArray, LargeBinaryArray, StringArray, StringViewArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::{
+    BinaryOutput, InvalidPatternMode, RegexpExtractConfig, regexp_extract_udf,
+    regexp_extract_udf_with,
+};
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+fn payloads() -> Vec<Option<&'static [u8]>> {
+    vec![
+        Some(b"id=42\xff\xfe".as_slice()),
+        Some(b"\x00\x01id=7".as_slice()),
+        None,
+        Some(b"nothing".as_slice()),
+    ]
+}
+
+async fn binary_ctx() -> SessionContext {
+    let ctx = SessionContext::new();
+    let b = Arc::new(BinaryArray::from(payloads())) as ArrayRef;
+    let lb = Arc::new(LargeBinaryArray::from(payloads())) as ArrayRef;
+    let bv = Arc::new(BinaryViewArray::from(payloads())) as ArrayRef;
+    ctx.register_table(
+        "t",
+        Arc::new(memtable(vec![("b", b), ("lb", lb), ("bv", bv)])),
+    )
+    .unwrap();
+    ctx
+}
+
+#[tokio::test]
+async fn binary_widths_return_binary() {
+    let ctx = binary_ctx().await;
+    let f = regexp_extract_udf();
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("b"), lit(r"id=(\d+)"), lit(1)]),
+            f.call(vec![col("lb"), lit(r"id=(\d+)"), lit(1_i64)]),
+            f.call(vec![col("bv"), lit(r"id=(\d+)"), lit(0)]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let b = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .unwrap();
+    assert_eq!(
+        b.iter().collect::<Vec<_>>(),
+        vec![
+            Some(b"42".as_slice()),
+            Some(b"7".as_slice()),
+            None,
+            Some(b"".as_slice())
+        ]
+    );
+    let lb = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<LargeBinaryArray>()
+        .unwrap();
+    assert_eq!(lb.value(0), b"42");
+    let bv = batches[0]
+        .column(2)
+        .as_any()
+        .downcast_ref::<BinaryViewArray>()
+        .unwrap();
+    assert_eq!(bv.value(1), b"id=7");
+    assert!(bv.is_null(2));
+}
+
+#[tokio::test]
+async fn non_utf8_bytes_and_lossy_output() {
+    let ctx = binary_ctx().await;
+    let lossy =
+        regexp_extract_udf_with(RegexpExtractConfig::new().binary_output(BinaryOutput::LossyUtf8));
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            // `(?-u)` lets classes match bytes that are not valid UTF-8
+            regexp_extract_udf().call(vec![col("b"), lit(r"(?-u)\d+([\x80-\xff]+)"), lit(1)]),
+            lossy.call(vec![col("b"), lit(r"(?-u)id=(.+)"), lit(1)]),
+            lossy.call(vec![col("bv"), lit(r"(?-u)id=(.+)"), lit(1)]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let raw = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .unwrap();
+    assert_eq!(raw.value(0), b"\xff\xfe");
+    assert_eq!(raw.value(1), b"");
+
+    assert_eq!(batches[0].column(1).data_type(), &DataType::Utf8);
+    let text = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(text.value(0), "42\u{FFFD}\u{FFFD}");
+    assert_eq!(text.value(1), "7");
+    assert!(text.is_null(2));
+
+    let view = batches[0]
+        .column(2)
+        .as_any()
+        .downcast_ref::<StringViewArray>()
+        .unwrap();
+    assert_eq!(view.value(1), "7");
+}
+
+#[tokio::test]
+async fn invalid_pattern_errors_or_is_empty() {
+    let ctx = binary_ctx().await;
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_udf().call(vec![
+            col("b"),
+            lit("(unclosed"),
+            lit(1),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("invalid pattern should error by default");
+    assert!(format!("{err:?}").contains("invalid regex pattern"));
+
+    let lenient = regexp_extract_udf_with(
+        RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::EmptyString),
+    );
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![lenient.call(vec![col("b"), lit("(unclosed"), lit(1)])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .unwrap();
+    assert_eq!(out.value(0), b"");
+    assert!(out.is_null(2));
+}
+
+#[cfg(feature = "fancy-regex")]
+#[tokio::test]
+async fn look_around_is_rejected_for_binary_input() {
+    let ctx = binary_ctx().await;
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_udf().call(vec![
+            col("b"),
+            lit(r"(?<=id=)\d+"),
+            lit(0),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("binary input has no look-around");
+    assert!(format!("{err}").contains("not supported for binary input"));
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, Int32Array, LargeStringArray, ListArray, StringArray,
+};
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col};
+
+use datafusion_regexp_extract_udf::{
+    RegexpExtractConfig, regexp_classify_udf, regexp_match_any_udf,
+};
+
+#[cfg(not(feature = "fancy-regex"))]
+use datafusion_regexp_extract_udf::InvalidPatternMode;
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+const PATTERNS: [&str; 3] = [r"^GET ", r"status=5\d\d", r"(?i)error"];
+
+fn events() -> ArrayRef {
+    Arc::new(StringArray::from(vec![
+        Some("GET /index status=200"),
+        Some("POST /upload status=503 ERROR"),
+        Some("GET /api status=500"),
+        Some("PUT /x status=201"),
+        None,
+    ])) as ArrayRef
+}
+
+#[tokio::test]
+async fn classify_returns_first_matching_label() {
+    let ctx = SessionContext::new();
+    let f = regexp_classify_udf(
+        &PATTERNS,
+        Some(&["read", "server_error", "error"]),
+        RegexpExtractConfig::new(),
+    )
+    .unwrap();
+    ctx.register_udf(f.clone());
+    ctx.register_table("t", Arc::new(memtable(vec![("s", events())])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s")]).alias("class")])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(out.value(0), "read");
+    assert_eq!(out.value(1), "server_error");
+    assert_eq!(out.value(2), "read"); // first in pattern order wins
+    assert!(out.is_null(3)); // no match
+    assert!(out.is_null(4)); // NULL input
+}
+
+#[tokio::test]
+async fn classify_defaults_labels_to_patterns_and_keeps_width() {
+    let ctx = SessionContext::new();
+    let f = regexp_classify_udf(&PATTERNS, None, RegexpExtractConfig::new()).unwrap();
+    let s = Arc::new(LargeStringArray::from(vec![Some("fatal Error")])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<LargeStringArray>()
+        .unwrap();
+    assert_eq!(out.value(0), "(?i)error");
+}
+
+#[tokio::test]
+async fn match_any_lists_all_matching_indices() {
+    let ctx = SessionContext::new();
+    let f = regexp_match_any_udf(&PATTERNS, RegexpExtractConfig::new()).unwrap();
+    ctx.register_table("t", Arc::new(memtable(vec![("s", events())])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s")]).alias("hits")])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    let row = |i: usize| {
+        out.value(i)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap()
+            .values()
+            .to_vec()
+    };
+    assert_eq!(row(0), vec![0]);
+    assert_eq!(row(1), vec![1, 2]);
+    assert_eq!(row(2), vec![0, 1]);
+    assert_eq!(row(3), Vec::<i32>::new());
+    assert!(out.is_null(4));
+}
+
+#[cfg(not(feature = "fancy-regex"))]
+#[tokio::test]
+async fn invalid_patterns_follow_invalid_pattern_mode() {
+    let err = regexp_match_any_udf(&["ok", "(?<=a)b"], RegexpExtractConfig::new()).unwrap_err();
+    assert!(err.to_string().contains("invalid regex pattern"));
+
+    // EmptyString: the invalid pattern never matches, indices still refer to the input list
+    let cfg = RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::EmptyString);
+    let f = regexp_match_any_udf(&["(?<=a)b", "b"], cfg).unwrap();
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![Some("ab")])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    let hits = out.value(0);
+    let hits = hits.as_any().downcast_ref::<Int32Array>().unwrap();
+    assert_eq!(hits.values().to_vec(), vec![1]);
+}
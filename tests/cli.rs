@@ -0,0 +1,113 @@
+#![cfg(feature = "cli")]
+
+use std::process::Command;
+
+fn regexp_extract(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_regexp-extract"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn temp_file(name: &str, contents: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("regexp-extract-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn csv_to_table() {
+    let input = temp_file(
+        "access.csv",
+        "id,line\n1,user=alice at 12:30\n2,user=bob at 09:05\n3,nothing\n",
+    );
+    let out = regexp_extract(&[
+        &input,
+        "-e",
+        r"user=line:user=(\w+):1",
+        "--extract",
+        r"min=line:(\d{2}):(\d{2}):2",
+    ]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let table = String::from_utf8(out.stdout).unwrap();
+    assert!(table.contains("| id | line"));
+    assert!(table.contains("| alice | 30  |"));
+    assert!(table.contains("| bob   | 05  |"));
+}
+
+#[test]
+fn ndjson_to_csv_with_config() {
+    let input = temp_file("events.ndjson", "{\"msg\":\"id=42\"}\n{\"msg\":\"x\"}\n");
+    let output = temp_file("ids.csv", "");
+    let out = regexp_extract(&[
+        &input,
+        "-e",
+        r"id=msg:id=(\d+):1",
+        "-e",
+        "bad=msg:(:1",
+        "--config",
+        "invalid_pattern_mode=collect",
+        "--only-extracted",
+        "-o",
+        &output,
+    ]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let csv = std::fs::read_to_string(&output).unwrap();
+    assert_eq!(csv.lines().next(), Some("id,bad"));
+    assert_eq!(csv.lines().nth(1), Some("42,"));
+    // collected errors are reported on stderr
+    assert!(String::from_utf8_lossy(&out.stderr).contains("bad: batch 0 row 0: InvalidPattern"));
+}
+
+#[test]
+fn bad_arguments_fail() {
+    let input = temp_file("bad.csv", "line\na\n");
+    let out = regexp_extract(&[&input, "-e", "no-spec"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("expected name=col:pattern:idx"));
+
+    let out = regexp_extract(&[&input, "-e", "x=line:a:0", "-c", "cache_size=many"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("invalid config `cache_size`"));
+}
+
+#[test]
+fn parquet_round_trip() {
+    let input = temp_file("orders.csv", "id,line\n1,sku=A-17\n2,sku=B-3\n3,none\n");
+    let parquet = temp_file("orders.parquet", "");
+    let out = regexp_extract(&[&input, "-e", r"sku=line:sku=([A-Z]-\d+):1", "-o", &parquet]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    // read the Parquet file back and extract from the column written above
+    let csv = temp_file("skus.csv", "");
+    let out = regexp_extract(&[
+        &parquet,
+        "-e",
+        r"n=sku:-(\d+):1",
+        "--only-extracted",
+        "-o",
+        &csv,
+    ]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let csv = std::fs::read_to_string(&csv).unwrap();
+    // the no-match row stays an empty string (quoted), not NULL
+    assert_eq!(csv.lines().collect::<Vec<_>>(), ["n", "17", "3", r#""""#]);
+}
@@ -0,0 +1,42 @@
+//! Fixtures shared by the integration tests that load `regexp_extract` through a foreign
+//! boundary (`ffi`, `python`).
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result;
+use datafusion::datasource::MemTable;
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::prelude::{SessionContext, col, lit};
+
+/// Runs `udf(s, p, 1)` over `s = ["a1", "b2", NULL]`, `p = [(\d), "(", (\d)]`: the second
+/// row's pattern is invalid, so the result shows the config's `invalid_pattern_mode`.
+pub async fn extract(udf: ScalarUDF) -> Result<Vec<Option<String>>> {
+    let ctx = SessionContext::new();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("s", DataType::Utf8, true),
+        Field::new("p", DataType::Utf8, true),
+    ]));
+    let s = Arc::new(StringArray::from(vec![Some("a1"), Some("b2"), None])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![r"(\d)", "(", r"(\d)"])) as ArrayRef;
+    let batch = RecordBatch::try_new(schema.clone(), vec![s, p]).unwrap();
+    let table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+    ctx.register_table("t", Arc::new(table)).unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![udf.call(vec![col("s"), col("p"), lit(1_i64)])])
+        .unwrap()
+        .collect()
+        .await?;
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    Ok(out.iter().map(|v| v.map(str::to_string)).collect())
+}
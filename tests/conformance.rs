@@ -0,0 +1,148 @@
+//! Spark conformance: runs every case of `tests/conformance/regexp_extract.docs-derived.jsonl`
+//! through `regexp_extract_udf_with` under the engine selected by the build features.
+//!
+//! Each case is `{id, input, pattern, idx, source}` plus either `expected` (Spark's result,
+//! `null` for NULL) or `expected_error_class` (Spark's error class). `incompatible` maps an
+//! engine name (`re::ENGINE`) to the reason that engine is known to disagree with Spark. The
+//! test fails on unexpected mismatches and on listed incompatibilities that now agree, so the
+//! corpus stays accurate either way.
+//!
+//! The corpus is not golden: every case is `source: docs`, derived from Spark's
+//! documentation and `java.util.regex` semantics rather than recorded from a Spark run.
+//! `capture_spark.py` records a `regexp_extract.spark-<version>.jsonl` from a real Spark
+//! (`source: spark-<version>`), which should replace it as `CORPUS` once checked in.
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::prelude::{SessionContext, col};
+use serde_json::Value;
+
+use datafusion_regexp_extract_udf::error::RegexpExtractError;
+use datafusion_regexp_extract_udf::{
+    RegexpExtractConfig, RowErrorKind, re, regexp_extract_udf_with,
+};
+
+const CORPUS: &str = include_str!("conformance/regexp_extract.docs-derived.jsonl");
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Value(Option<String>),
+    Error(String),
+}
+
+struct Case {
+    id: String,
+    input: Option<String>,
+    pattern: Option<String>,
+    idx: Option<i64>,
+    expected: Outcome,
+    source: String,
+    incompatible: Option<String>,
+}
+
+fn parse(line: &str) -> Case {
+    let v: Value = serde_json::from_str(line).unwrap_or_else(|e| panic!("{e}: {line}"));
+    let text = |key: &str| v[key].as_str().map(str::to_string);
+    let expected = match text("expected_error_class") {
+        Some(class) => Outcome::Error(class),
+        None => Outcome::Value(text("expected")),
+    };
+    Case {
+        id: text("id").expect("case id"),
+        input: text("input"),
+        pattern: text("pattern"),
+        idx: v["idx"].as_i64(),
+        expected,
+        source: text("source").expect("case source"),
+        incompatible: v["incompatible"][re::ENGINE].as_str().map(str::to_string),
+    }
+}
+
+/// Spark's error class for the errors both sides raise.
+fn spark_error_class(err: &datafusion::common::DataFusionError) -> String {
+    match RegexpExtractError::from_datafusion(err).map(RegexpExtractError::kind) {
+        Some(RowErrorKind::InvalidPattern) => "INVALID_PARAMETER_VALUE.PATTERN".to_string(),
+        Some(RowErrorKind::NegativeIndex | RowErrorKind::GroupIndexOutOfRange) => {
+            "INVALID_PARAMETER_VALUE.REGEX_GROUP_INDEX".to_string()
+        }
+        _ => format!("UNMAPPED: {err}"),
+    }
+}
+
+async fn run(case: &Case) -> Outcome {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("s", datafusion::arrow::datatypes::DataType::Utf8, true),
+        Field::new("p", datafusion::arrow::datatypes::DataType::Utf8, true),
+        Field::new("idx", datafusion::arrow::datatypes::DataType::Int64, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(StringArray::from(vec![case.input.clone()])) as ArrayRef,
+            Arc::new(StringArray::from(vec![case.pattern.clone()])) as ArrayRef,
+            Arc::new(Int64Array::from(vec![case.idx])) as ArrayRef,
+        ],
+    )
+    .unwrap();
+    let udf = regexp_extract_udf_with(RegexpExtractConfig::new());
+    let result = SessionContext::new()
+        .read_batch(batch)
+        .unwrap()
+        .select(vec![udf.call(vec![col("s"), col("p"), col("idx")])])
+        .unwrap()
+        .collect()
+        .await;
+    match result {
+        Ok(batches) => {
+            let out = batches[0]
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            Outcome::Value(out.iter().next().unwrap().map(str::to_string))
+        }
+        Err(err) => Outcome::Error(spark_error_class(&err)),
+    }
+}
+
+#[tokio::test]
+async fn regexp_extract_matches_expected_spark_output() {
+    let (mut passed, mut known, mut mismatches, mut stale) =
+        (0, Vec::new(), Vec::new(), Vec::new());
+    let mut sources = std::collections::BTreeMap::<_, usize>::new();
+    for line in CORPUS.lines().filter(|l| !l.trim().is_empty()) {
+        let case = parse(line);
+        *sources.entry(case.source.clone()).or_default() += 1;
+        let actual = run(&case).await;
+        match (&case.incompatible, actual == case.expected) {
+            (None, true) => passed += 1,
+            (None, false) => mismatches.push(format!(
+                "{}: expected {:?}, got {actual:?}",
+                case.id, case.expected
+            )),
+            (Some(why), false) => known.push(format!("{}: {why}", case.id)),
+            (Some(_), true) => stale.push(case.id),
+        }
+    }
+
+    eprintln!(
+        "conformance ({}): {passed} passed; cases by source: {sources:?}",
+        re::ENGINE
+    );
+    for k in &known {
+        eprintln!("  known incompatible: {k}");
+    }
+    assert!(
+        mismatches.is_empty(),
+        "mismatches with Spark:\n{}",
+        mismatches.join("\n")
+    );
+    assert!(
+        stale.is_empty(),
+        "listed as incompatible for `{}` but now agree with Spark: {stale:?}",
+        re::ENGINE
+    );
+}
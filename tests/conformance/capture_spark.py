@@ -0,0 +1,63 @@
+"""Record the Spark output of every case in regexp_extract.docs-derived.jsonl.
+
+    pip install pyspark==3.5.*
+    python tests/conformance/capture_spark.py tests/conformance/regexp_extract.docs-derived.jsonl
+
+Writes regexp_extract.spark-<version>.jsonl next to the input: `expected` /
+`expected_error_class` come from Spark and `source` becomes `spark-<version>`; `id`, the
+inputs and `incompatible` are kept. Diff it against the input before checking it in and
+pointing `CORPUS` in tests/conformance.rs at it.
+"""
+
+import json
+import os
+import re
+import sys
+
+from pyspark.errors import PySparkException
+from pyspark.sql import SparkSession
+from pyspark.sql.types import LongType, StringType, StructField, StructType
+
+SCHEMA = StructType(
+    [
+        StructField("s", StringType()),
+        StructField("p", StringType()),
+        StructField("idx", LongType()),
+    ]
+)
+
+
+def capture(spark, case):
+    df = spark.createDataFrame([(case["input"], case["pattern"], case["idx"])], SCHEMA)
+    try:
+        # idx is cast like Spark's implicit Int coercion for literals
+        row = df.selectExpr("regexp_extract(s, p, CAST(idx AS INT)) AS r").collect()[0]
+        return {"expected": row["r"]}
+    except PySparkException as e:
+        # task failures arrive wrapped; the cause's class is tagged in the message
+        tag = re.search(r"\[([A-Z_]+(?:\.[A-Z_]+)*)\]", str(e))
+        return {"expected_error_class": e.getErrorClass() or (tag and tag.group(1))}
+
+
+def main(path):
+    spark = SparkSession.builder.master("local[1]").getOrCreate()
+    with open(path, encoding="utf-8") as f:
+        cases = [json.loads(line) for line in f if line.strip()]
+    out = []
+    for case in cases:
+        recorded = {k: case[k] for k in ("id", "input", "pattern", "idx")}
+        recorded["source"] = f"spark-{spark.version}"
+        recorded.update(capture(spark, case))
+        if "incompatible" in case:
+            recorded["incompatible"] = case["incompatible"]
+        out.append(json.dumps(recorded, ensure_ascii=False))
+    out_path = os.path.join(
+        os.path.dirname(path), f"regexp_extract.spark-{spark.version}.jsonl"
+    )
+    with open(out_path, "w", encoding="utf-8") as f:
+        f.write("\n".join(out) + "\n")
+    spark.stop()
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
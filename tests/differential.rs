@@ -0,0 +1,319 @@
+//! Differential property tests: every kernel path (no-nulls fast path, null-aware loop,
+//! grouped column patterns, scalar pattern/idx, all width combinations, `parallel` ranges)
+//! must agree with a row-by-row reference built directly on the `regex` crate. Under
+//! `fancy-regex` this also compares the two engines, on patterns whose validity they agree
+//! on.
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, Int64Array, LargeStringArray, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::ScalarValue;
+use datafusion::logical_expr::{ColumnarValue, ScalarFunctionArgs};
+use proptest::collection::vec;
+use proptest::option::weighted;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use datafusion_regexp_extract_udf::error::RegexpExtractError;
+use datafusion_regexp_extract_udf::{
+    ErrorLog, InvalidPatternMode, RegexpExtractConfig, RowErrorKind, re, regexp_extract_udf_with,
+};
+
+/// Pattern pieces; concatenations include unbalanced groups and dangling quantifiers.
+const TOKENS: &[&str] = &[
+    "a", "b", "é", "1", r"\d", r"\w", ".", "(a)", "(b|1)", "(é+)", r"(\d+)", "(a)?", "(a|b)*",
+    "[ab]", "[^a]", "^", "$", "(?i)A", "(?:ab)", r"\b", "*", "+", "?", "(", ")", "|", "{2}",
+];
+
+const CHARS: &[char] = &['a', 'b', '1', '2', 'é', 'A', ' ', '-'];
+
+type Row = (Option<String>, Option<usize>, Option<i64>);
+
+/// Error kind and batch row, the parts both sides can agree on.
+type Failure = (RowErrorKind, Option<usize>);
+
+#[derive(Debug, Clone)]
+struct Case {
+    pool: Vec<String>,
+    rows: Vec<Row>,
+}
+
+fn pattern() -> impl Strategy<Value = String> {
+    vec(select(TOKENS), 1..5)
+        .prop_map(|t| t.concat())
+        .prop_filter("engines disagree on validity", |p| {
+            re::compile(p).is_ok() == regex::Regex::new(p).is_ok()
+        })
+        .prop_filter("stacked quantifiers differ by engine", |p| {
+            !stacked_quantifier(p)
+        })
+}
+
+/// `a*{2}`, `a+*` and the like: `regex` nests them, `fancy-regex` rejects or reads them
+/// differently. A trailing `?` (lazy) means the same in both.
+fn stacked_quantifier(p: &str) -> bool {
+    let b = p.as_bytes();
+    b.windows(2)
+        .any(|w| matches!(w[0], b'*' | b'+' | b'?' | b'}') && matches!(w[1], b'*' | b'+' | b'{'))
+}
+
+fn case() -> impl Strategy<Value = Case> {
+    vec(pattern(), 1..4).prop_flat_map(|pool| {
+        let n = pool.len();
+        let text = vec(select(CHARS), 0..8).prop_map(String::from_iter);
+        let idx = prop_oneof![8 => 0i64..4, 1 => Just(-1i64)];
+        let rows = vec(
+            (
+                weighted(0.85, text),
+                weighted(0.85, 0..n),
+                weighted(0.85, idx),
+            ),
+            1..40,
+        );
+        // half the cases have no NULLs at all, so the fast path runs too
+        (Just(pool), rows, any::<bool>()).prop_map(|(pool, mut rows, no_nulls)| {
+            if no_nulls {
+                for (s, p, i) in &mut rows {
+                    s.get_or_insert_with(String::new);
+                    p.get_or_insert(0);
+                    i.get_or_insert(0);
+                }
+            }
+            Case { pool, rows }
+        })
+    })
+}
+
+fn reference(
+    s: Option<&str>,
+    p: Option<&str>,
+    idx: Option<i64>,
+) -> Result<Option<String>, RowErrorKind> {
+    let (Some(s), Some(p), Some(idx)) = (s, p, idx) else {
+        return Ok(None);
+    };
+    if idx < 0 {
+        return Err(RowErrorKind::NegativeIndex);
+    }
+    let re = regex::Regex::new(p).map_err(|_| RowErrorKind::InvalidPattern)?;
+    let group = re
+        .captures(s)
+        .and_then(|c| c.get(idx as usize))
+        .map_or("", |m| m.as_str());
+    Ok(Some(group.to_string()))
+}
+
+/// `Error` mode: the row values, or the first failing row. An invalid `scalar` pattern
+/// fails before any row is looked at.
+fn expect_error_mode(
+    rows: &[Result<Option<String>, RowErrorKind>],
+    scalar: Option<&str>,
+) -> Result<Vec<Option<String>>, Failure> {
+    if scalar.is_some_and(|p| regex::Regex::new(p).is_err()) {
+        return Err((RowErrorKind::InvalidPattern, None));
+    }
+    rows.iter()
+        .enumerate()
+        .map(|(i, r)| r.clone().map_err(|k| (k, Some(i))))
+        .collect()
+}
+
+/// `Collect` mode: failing rows are NULL and logged.
+fn expect_collect_mode(
+    rows: &[Result<Option<String>, RowErrorKind>],
+) -> (Vec<Option<String>>, Vec<Failure>) {
+    let values = rows.iter().map(|r| r.clone().unwrap_or(None)).collect();
+    let errors = rows
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| r.as_ref().err().map(|k| (*k, Some(i))))
+        .collect();
+    (values, errors)
+}
+
+fn strings_of(a: &ArrayRef) -> Vec<Option<String>> {
+    if let Some(a) = a.as_any().downcast_ref::<StringArray>() {
+        return a.iter().map(|v| v.map(str::to_string)).collect();
+    }
+    let a = a.as_any().downcast_ref::<LargeStringArray>().unwrap();
+    a.iter().map(|v| v.map(str::to_string)).collect()
+}
+
+fn invoke(
+    cfg: RegexpExtractConfig,
+    args: Vec<ColumnarValue>,
+    rows: usize,
+) -> Result<Vec<Option<String>>, Failure> {
+    let udf = regexp_extract_udf_with(cfg);
+    let types = args.iter().map(|a| a.data_type()).collect::<Vec<_>>();
+    let arg_fields = types
+        .iter()
+        .enumerate()
+        .map(|(i, t)| Arc::new(Field::new(format!("a{i}"), t.clone(), true)))
+        .collect();
+    let return_field = Arc::new(Field::new("r", udf.return_type(&types).unwrap(), true));
+    let out = udf.invoke_with_args(ScalarFunctionArgs {
+        args,
+        arg_fields,
+        number_rows: rows,
+        return_field,
+    });
+    match out {
+        Ok(cv) => Ok(strings_of(&cv.into_array(rows).unwrap())),
+        Err(e) => {
+            let e = RegexpExtractError::from_datafusion(&e)
+                .unwrap_or_else(|| panic!("not a RegexpExtractError: {e}"));
+            Err((e.kind(), e.row()))
+        }
+    }
+}
+
+/// Columns for `rows`, strings/patterns as `Utf8` and idx as `Int64`.
+fn columns(pool: &[String], rows: &[Row]) -> (ArrayRef, ArrayRef, ArrayRef) {
+    let s = StringArray::from(rows.iter().map(|r| r.0.clone()).collect::<Vec<_>>());
+    let p = StringArray::from(
+        rows.iter()
+            .map(|r| r.1.map(|i| pool[i].clone()))
+            .collect::<Vec<_>>(),
+    );
+    let i = Int64Array::from(rows.iter().map(|r| r.2).collect::<Vec<_>>());
+    (Arc::new(s), Arc::new(p), Arc::new(i))
+}
+
+fn arrays(a: &[&ArrayRef]) -> Vec<ColumnarValue> {
+    a.iter()
+        .map(|a| ColumnarValue::Array(Arc::clone(a)))
+        .collect()
+}
+
+fn error_mode() -> RegexpExtractConfig {
+    RegexpExtractConfig::new()
+}
+
+fn check_collect(
+    cfg: RegexpExtractConfig,
+    args: Vec<ColumnarValue>,
+    expected: &[Result<Option<String>, RowErrorKind>],
+) -> Result<(), TestCaseError> {
+    let log = ErrorLog::new();
+    let cfg = cfg
+        .invalid_pattern_mode(InvalidPatternMode::Collect)
+        .error_log(log.clone());
+    let values = invoke(cfg, args, expected.len()).expect("collect never fails");
+    let mut logged = log
+        .take()
+        .into_iter()
+        .map(|e| (e.kind, Some(e.row)))
+        .collect::<Vec<_>>();
+    logged.sort_by_key(|e| e.1);
+    prop_assert_eq!((values, logged), expect_collect_mode(expected));
+    Ok(())
+}
+
+/// `rows` repeated up to `min_rows`, so the grouped / parallel paths apply.
+fn tiled<T: Clone>(rows: &[T], min_rows: usize) -> Vec<T> {
+    rows.iter()
+        .cycle()
+        .take(min_rows.max(rows.len()))
+        .cloned()
+        .collect()
+}
+
+/// A length-1 pattern column is evaluated as a scalar pattern.
+fn one_row_pattern<'a>(pool: &'a [String], rows: &[Row]) -> Option<&'a str> {
+    match rows {
+        [(_, Some(p), _)] => Some(pool[*p].as_str()),
+        _ => None,
+    }
+}
+
+fn reference_rows(pool: &[String], rows: &[Row]) -> Vec<Result<Option<String>, RowErrorKind>> {
+    rows.iter()
+        .map(|(s, p, i)| reference(s.as_deref(), p.map(|p| pool[p].as_str()), *i))
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn column_paths_match_reference(case in case()) {
+        let n = case.rows.len();
+        let expected = reference_rows(&case.pool, &case.rows);
+        let (s, p, i) = columns(&case.pool, &case.rows);
+
+        let want = expect_error_mode(&expected, one_row_pattern(&case.pool, &case.rows));
+        let i32s = cast(&i, &DataType::Int32).unwrap();
+        for s_dt in [DataType::Utf8, DataType::LargeUtf8] {
+            for p_dt in [DataType::Utf8, DataType::LargeUtf8] {
+                let s = cast(&s, &s_dt).unwrap();
+                let p = cast(&p, &p_dt).unwrap();
+                for i in [&i, &i32s] {
+                    let got = invoke(error_mode(), arrays(&[&s, &p, i]), n);
+                    prop_assert_eq!(&got, &want, "{:?} {:?} {:?}", s_dt, p_dt, i.data_type());
+                }
+            }
+        }
+        check_collect(RegexpExtractConfig::new(), arrays(&[&s, &p, &i]), &expected)?;
+    }
+
+    #[test]
+    fn grouped_path_matches_reference(case in case()) {
+        let rows = tiled(&case.rows, 512);
+        let expected = tiled(&reference_rows(&case.pool, &case.rows), rows.len());
+        let (s, p, i) = columns(&case.pool, &rows);
+
+        let got = invoke(error_mode(), arrays(&[&s, &p, &i]), rows.len());
+        prop_assert_eq!(got, expect_error_mode(&expected, None));
+        check_collect(RegexpExtractConfig::new(), arrays(&[&s, &p, &i]), &expected)?;
+    }
+
+    #[test]
+    fn scalar_arguments_match_reference(case in case()) {
+        let n = case.rows.len();
+        let (s, p, i) = columns(&case.pool, &case.rows);
+
+        // scalar pattern: an invalid one fails before any row
+        let pat = case.pool[0].clone();
+        let rows = case.rows.iter().map(|r| (r.0.clone(), Some(0), r.2)).collect::<Vec<_>>();
+        let expected = reference_rows(&case.pool, &rows);
+        let scalar_p = ColumnarValue::Scalar(ScalarValue::Utf8(Some(pat.clone())));
+        let args = vec![ColumnarValue::Array(s.clone()), scalar_p.clone(), ColumnarValue::Array(i.clone())];
+        let want = expect_error_mode(&expected, Some(&pat));
+        prop_assert_eq!(invoke(error_mode(), args.clone(), n), want);
+        check_collect(RegexpExtractConfig::new(), args, &expected)?;
+
+        // scalar idx, NULL included
+        let idx = case.rows[0].2;
+        let rows = case.rows.iter().map(|r| (r.0.clone(), r.1, idx)).collect::<Vec<_>>();
+        let expected = reference_rows(&case.pool, &rows);
+        let args = vec![
+            ColumnarValue::Array(s.clone()),
+            ColumnarValue::Array(p.clone()),
+            ColumnarValue::Scalar(ScalarValue::Int64(idx)),
+        ];
+        let want = expect_error_mode(&expected, one_row_pattern(&case.pool, &rows));
+        prop_assert_eq!(invoke(error_mode(), args.clone(), n), want);
+        check_collect(RegexpExtractConfig::new(), args, &expected)?;
+    }
+}
+
+#[cfg(feature = "parallel")]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn parallel_ranges_match_reference(case in case()) {
+        // several rayon ranges (at least 4096 rows each)
+        let rows = tiled(&case.rows, 3 * 4096);
+        let expected = tiled(&reference_rows(&case.pool, &case.rows), rows.len());
+        let (s, p, i) = columns(&case.pool, &rows);
+        let cfg = RegexpExtractConfig::new().parallel_threshold(1);
+
+        let got = invoke(cfg.clone(), arrays(&[&s, &p, &i]), rows.len());
+        prop_assert_eq!(got, expect_error_mode(&expected, None));
+        check_collect(cfg, arrays(&[&s, &p, &i]), &expected)?;
+    }
+}
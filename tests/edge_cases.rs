@@ -1,11 +1,19 @@
-use datafusion::arrow::array::{ArrayRef, LargeStringArray, StringArray};
+use datafusion::arrow::array::{
+    Array, ArrayRef, Int32Array, Int64Array, LargeStringArray, StringArray,
+};
 use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::datasource::MemTable;
+use datafusion::logical_expr::{ColumnarValue, ScalarFunctionArgs, ScalarUDF};
 use datafusion::prelude::{SessionContext, col, lit};
 use std::sync::Arc;
 
-use datafusion_regexp_extract_udf::regexp_extract_udf;
+use datafusion_regexp_extract_udf::error::RegexpExtractError;
+use datafusion_regexp_extract_udf::kernel::{run_large_utf8_largeutf8, run_utf8_utf8};
+use datafusion_regexp_extract_udf::pattern_cache::PatternCache;
+use datafusion_regexp_extract_udf::{
+    InvalidPatternMode, regexp_extract_udf, regexp_instr_udf, rlike_udf, split_udf,
+};
 
 fn memtable(cols: Vec<(&str, DataType, ArrayRef)>) -> MemTable {
     let fields = cols
@@ -164,3 +172,291 @@ async fn long_strings_varying_lengths() {
     assert_eq!(digits.value(1), "123");
     assert_eq!(digits.value(2), "123");
 }
+
+#[tokio::test]
+async fn alternating_patterns_with_different_group_counts() {
+    let ctx = SessionContext::new();
+    ctx.register_udf(regexp_extract_udf());
+
+    // capture buffer is rebuilt whenever the row's pattern changes
+    let s = Arc::new(StringArray::from(vec![
+        Some("ab"),
+        Some("ab"),
+        Some("xyz"),
+        Some("ab"),
+    ])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![
+        Some(r"(a)(b)"),
+        Some(r"(a)"),
+        Some(r"(x)(y)(z)"),
+        Some(r"(a)(b)"),
+    ])) as ArrayRef;
+    let t = memtable(vec![("s", DataType::Utf8, s), ("p", DataType::Utf8, p)]);
+    ctx.register_table("t", Arc::new(t)).unwrap();
+
+    let f = regexp_extract_udf();
+    let df = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), col("p"), lit(2)]).alias("g2")])
+        .unwrap();
+
+    let batches = df.collect().await.unwrap();
+    let g2 = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(g2.value(0), "b");
+    assert_eq!(g2.value(1), "");
+    assert_eq!(g2.value(2), "y");
+    assert_eq!(g2.value(3), "b");
+}
+
+#[tokio::test]
+async fn shuffled_column_patterns_are_grouped_and_kept_in_row_order() {
+    let ctx = SessionContext::new();
+    ctx.register_udf(regexp_extract_udf());
+
+    // 1024 rows over 3 patterns in scrambled order: takes the grouped path
+    let pats = [r"(\d+)-(\d+)", r"([a-z]+)=(\d+)", r"(x)(y)?"];
+    let n = 1024;
+    let s = Arc::new(StringArray::from(
+        (0..n)
+            .map(|i| match i % 5 {
+                0 => None,
+                1 => Some("12-34"),
+                2 => Some("key=7"),
+                3 => Some("x"),
+                _ => Some("nothing"),
+            })
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let p = Arc::new(StringArray::from(
+        (0..n)
+            .map(|i| (i % 13 != 0).then_some(pats[(i * 7) % 3]))
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let t = memtable(vec![("s", DataType::Utf8, s), ("p", DataType::Utf8, p)]);
+    ctx.register_table("t", Arc::new(t)).unwrap();
+
+    let f = regexp_extract_udf();
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), col("p"), lit(2)]).alias("g2")])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let g2 = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+
+    assert_eq!(g2.len(), n);
+    for i in 0..n {
+        if i % 5 == 0 || i % 13 == 0 {
+            assert!(g2.is_null(i), "row {i}");
+            continue;
+        }
+        let expected = match (i % 5, (i * 7) % 3) {
+            (1, 0) => "34",
+            (2, 1) => "7",
+            _ => "",
+        };
+        assert_eq!(g2.value(i), expected, "row {i}");
+    }
+}
+
+#[tokio::test]
+async fn grouped_column_patterns_report_invalid_pattern() {
+    let ctx = SessionContext::new();
+    ctx.register_udf(regexp_extract_udf());
+
+    let n = 1024;
+    let s = Arc::new(StringArray::from(vec![Some("a1"); n])) as ArrayRef;
+    let p = Arc::new(StringArray::from(
+        (0..n)
+            .map(|i| {
+                Some(if i == 700 {
+                    "(unclosed"
+                } else {
+                    r"([a-z])(\d)"
+                })
+            })
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let t = memtable(vec![("s", DataType::Utf8, s), ("p", DataType::Utf8, p)]);
+    ctx.register_table("t", Arc::new(t)).unwrap();
+
+    let f = regexp_extract_udf();
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), col("p"), lit(1)])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("invalid pattern should fail the batch");
+    assert!(format!("{err:?}").contains("invalid regex pattern"));
+}
+
+#[test]
+fn kernel_rejects_mismatched_lengths() {
+    let s = StringArray::from(vec!["a1", "b2", "c3"]);
+    let two = StringArray::from(vec![r"(\d)", r"(\d)"]);
+    let idx = Int64Array::from(vec![1]);
+    let mode = InvalidPatternMode::Error;
+
+    let err = run_utf8_utf8(&s, &two, Some(&idx), None, &DataType::Utf8, 4, mode).unwrap_err();
+    assert!(matches!(
+        err,
+        RegexpExtractError::LengthMismatch {
+            argument: "pattern",
+            rows: 3,
+            len: 2
+        }
+    ));
+
+    let p = StringArray::from(vec![r"(\d)"]);
+    let short = Int32Array::from(vec![1, 1]);
+    let err = run_utf8_utf8(&s, &p, None, Some(&short), &DataType::Utf8, 4, mode).unwrap_err();
+    assert!(
+        err.to_string().contains("idx has 2 values for 3 rows"),
+        "{err}"
+    );
+
+    let err = run_utf8_utf8(&s, &p, None, None, &DataType::Utf8, 4, mode).unwrap_err();
+    assert!(matches!(err, RegexpExtractError::MissingIdxArray));
+}
+
+/// Invokes `udf` directly, so argument arrays can disagree with `number_rows`.
+fn invoke(udf: &ScalarUDF, args: Vec<ArrayRef>) -> datafusion::common::Result<ColumnarValue> {
+    let types = args
+        .iter()
+        .map(|a| a.data_type().clone())
+        .collect::<Vec<_>>();
+    let arg_fields = types
+        .iter()
+        .enumerate()
+        .map(|(i, t)| Arc::new(Field::new(format!("a{i}"), t.clone(), true)))
+        .collect();
+    let return_field = Arc::new(Field::new("r", udf.return_type(&types).unwrap(), true));
+    udf.invoke_with_args(ScalarFunctionArgs {
+        number_rows: args[0].len(),
+        args: args.into_iter().map(ColumnarValue::Array).collect(),
+        arg_fields,
+        return_field,
+    })
+}
+
+#[test]
+fn udfs_reject_mismatched_lengths() {
+    let s = Arc::new(StringArray::from(vec!["a1", "b2", "c3"])) as ArrayRef;
+    let two = Arc::new(StringArray::from(vec![r"(\d)", r"(\d)"])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![r"(\d)"])) as ArrayRef;
+    let two_i64 = Arc::new(Int64Array::from(vec![1, 1])) as ArrayRef;
+
+    let cases = [
+        (rlike_udf(), vec![s.clone(), two.clone()], "pattern"),
+        (regexp_instr_udf(), vec![s.clone(), two.clone()], "pattern"),
+        (
+            regexp_instr_udf(),
+            vec![s.clone(), p.clone(), two_i64.clone()],
+            "idx",
+        ),
+        (split_udf(), vec![s.clone(), two], "pattern"),
+        (split_udf(), vec![s, p, two_i64], "limit"),
+    ];
+    for (udf, args, name) in cases {
+        let err = invoke(&udf, args).unwrap_err();
+        assert!(
+            matches!(
+                RegexpExtractError::from_datafusion(&err),
+                Some(RegexpExtractError::LengthMismatch { argument, rows: 3, len: 2 })
+                    if *argument == name
+            ),
+            "{}: {err}",
+            udf.name()
+        );
+    }
+}
+
+#[test]
+fn kernel_null_scalar_idx_gives_null() {
+    // a one-value NULL idx is a NULL scalar: every row is NULL, whichever loop runs
+    let mode = InvalidPatternMode::Error;
+    let i64s = Int64Array::from(vec![None::<i64>]);
+    let i32s = Int32Array::from(vec![None::<i32>]);
+    let strings = [
+        vec![Some("a1"), Some("b2"), Some("c3")],
+        vec![Some("a1"), None, Some("c3")],
+    ];
+    let patterns = [vec![r"(\d)"], vec![r"(\d)", r"([a-z])", r"(\d)"]];
+    let idx = [(Some(&i64s), None), (None, Some(&i32s))];
+    for s in &strings {
+        for p in &patterns {
+            for (idx_i64, idx_i32) in idx {
+                let out = run_utf8_utf8(
+                    &StringArray::from(s.clone()),
+                    &StringArray::from(p.clone()),
+                    idx_i64,
+                    idx_i32,
+                    &DataType::Utf8,
+                    4,
+                    mode,
+                )
+                .unwrap();
+                assert_eq!(out.null_count(), 3, "{s:?} {p:?}");
+
+                let out = run_large_utf8_largeutf8(
+                    &LargeStringArray::from(s.clone()),
+                    &LargeStringArray::from(p.clone()),
+                    idx_i64,
+                    idx_i32,
+                    &DataType::LargeUtf8,
+                    4,
+                    mode,
+                )
+                .unwrap();
+                assert_eq!(out.null_count(), 3, "{s:?} {p:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn pattern_cache_counts_hits_and_failed_compiles() {
+    let mut cache = PatternCache::new(0);
+    assert!(cache.get_or_compile("a+").is_ok());
+    assert!(cache.get_or_compile("a+").is_ok());
+    assert!(cache.get_or_compile("(").is_err());
+    assert!(cache.get_or_compile("(").is_err());
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.compiled), (1, 3, 1));
+}
+
+#[test]
+fn capture_buffer_survives_pattern_changes() {
+    // alternating patterns with equal and different group counts share one buffer
+    let s = StringArray::from(vec!["ab", "xy", "k7", "ab", "q-r", "xy"]);
+    let p = StringArray::from(vec![
+        "(a)(b)",
+        "(?<first>x)(y)",
+        r"k(\d)",
+        "(a)(b)",
+        r"(\w)-(\w)",
+        "(x)(y)",
+    ]);
+    let idx = Int64Array::from(vec![2, 1, 1, 1, 2, 2]);
+    let mode = InvalidPatternMode::Error;
+    let out = run_utf8_utf8(&s, &p, Some(&idx), None, &DataType::Utf8, 1, mode).unwrap();
+    let out = out.as_any().downcast_ref::<StringArray>().unwrap();
+    let got = (0..out.len()).map(|i| out.value(i)).collect::<Vec<_>>();
+    assert_eq!(got, ["b", "x", "7", "a", "r", "y"]);
+}
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, BooleanArray, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionConfig, SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::{
+    ErrorLog, InvalidPatternMode, RegexpExtractConfig, RowErrorKind, regexp_extract_udf,
+    regexp_extract_udf_with, rlike_udf_with,
+};
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+fn ctx_with_bad_rows() -> SessionContext {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec!["a1", "b2", "c3", "d4"])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![
+        r"([a-z])(\d)",
+        "(unclosed",
+        r"([a-z])(\d)",
+        r"(\d)",
+    ])) as ArrayRef;
+    let idx = Arc::new(Int64Array::from(vec![2, 1, -1, 1])) as ArrayRef;
+    ctx.register_table(
+        "t",
+        Arc::new(memtable(vec![("s", s), ("p", p), ("idx", idx)])),
+    )
+    .unwrap();
+    ctx
+}
+
+#[tokio::test]
+async fn collect_nulls_bad_rows_and_logs_them() {
+    let ctx = ctx_with_bad_rows();
+    let log = ErrorLog::new();
+    let f = regexp_extract_udf_with(
+        RegexpExtractConfig::new()
+            .invalid_pattern_mode(InvalidPatternMode::Collect)
+            .error_log(log.clone()),
+    );
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), col("p"), col("idx")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(
+        out.iter().collect::<Vec<_>>(),
+        vec![Some("1"), None, None, Some("4")]
+    );
+
+    let errors = log.take();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].row, 1);
+    assert_eq!(errors[0].kind, RowErrorKind::InvalidPattern);
+    assert_eq!(errors[0].pattern, "(unclosed");
+    assert_eq!(errors[1].row, 2);
+    assert_eq!(errors[1].kind, RowErrorKind::NegativeIndex);
+    assert!(errors[1].message.contains("got -1"));
+    assert!(log.is_empty());
+}
+
+#[tokio::test]
+async fn collect_scalar_pattern_reports_every_row() {
+    let ctx = ctx_with_bad_rows();
+    let log = ErrorLog::new();
+    let f = rlike_udf_with(
+        RegexpExtractConfig::new()
+            .invalid_pattern_mode(InvalidPatternMode::Collect)
+            .error_log(log.clone()),
+    );
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), lit("[bad")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .unwrap();
+    assert_eq!(out.null_count(), 4);
+    let rows = log.take().into_iter().map(|e| e.row).collect::<Vec<_>>();
+    assert_eq!(rows, vec![0, 1, 2, 3]);
+}
+
+#[tokio::test]
+async fn error_messages_name_row_and_pattern() {
+    let ctx = ctx_with_bad_rows();
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_udf().call(vec![
+            col("s"),
+            col("p"),
+            col("idx"),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("row 1 has an invalid pattern");
+    let msg = err.to_string();
+    assert!(msg.contains("invalid regex pattern"), "{msg}");
+    assert!(msg.contains("row 1, pattern `(unclosed`"), "{msg}");
+
+    // long patterns are cut in the location suffix
+    let long = format!("({}", "x".repeat(200));
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_udf().call(vec![
+            col("s"),
+            lit(long.as_str()),
+            lit(0),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("invalid scalar pattern");
+    let msg = err.to_string();
+    assert!(
+        msg.contains(&format!("pattern `({}…`", "x".repeat(63))),
+        "{msg}"
+    );
+}
+
+#[tokio::test]
+async fn collect_in_grouped_column_patterns() {
+    // enough rows with few distinct patterns to take the grouped path
+    let rows = 512;
+    let s = Arc::new(StringArray::from(
+        (0..rows).map(|r| format!("k{r}")).collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let p = Arc::new(StringArray::from(
+        (0..rows)
+            .map(|r| match r % 4 {
+                1 => "q(",
+                3 => "k(",
+                _ => r"k(\d+)",
+            })
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let ctx = SessionContext::new();
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("p", p)])))
+        .unwrap();
+
+    let log = ErrorLog::new();
+    let f = regexp_extract_udf_with(
+        RegexpExtractConfig::new()
+            .invalid_pattern_mode(InvalidPatternMode::Collect)
+            .error_log(log.clone()),
+    );
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), col("p"), lit(1)])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(out.value(2), "2");
+    assert!(out.is_null(3));
+    assert_eq!(out.value(4), "4");
+    // errors of both pattern groups come back in row order
+    let rows = log.take().into_iter().map(|e| e.row).collect::<Vec<_>>();
+    assert_eq!(rows, (1..512).step_by(2).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn logged_errors_carry_their_batch() {
+    let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+    let batch = |v: Vec<&str>| {
+        let s = Arc::new(StringArray::from(v)) as ArrayRef;
+        RecordBatch::try_new(schema.clone(), vec![s]).unwrap()
+    };
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![vec![batch(vec!["a", "b"]), batch(vec!["c", "d", "e"])]],
+    )
+    .unwrap();
+    let ctx = SessionContext::new_with_config(SessionConfig::new().with_target_partitions(1));
+    ctx.register_table("t", Arc::new(table)).unwrap();
+
+    let log = ErrorLog::new();
+    let f = regexp_extract_udf_with(
+        RegexpExtractConfig::new()
+            .invalid_pattern_mode(InvalidPatternMode::Collect)
+            .error_log(log.clone()),
+    );
+    ctx.table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), lit("(s"), lit(1)])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let rows = log
+        .take()
+        .into_iter()
+        .map(|e| (e.batch, e.row))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![
+            (Some(0), 0),
+            (Some(0), 1),
+            (Some(1), 0),
+            (Some(1), 1),
+            (Some(1), 2)
+        ]
+    );
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use common::extract;
+use datafusion::logical_expr::ScalarUDF;
+use datafusion_ffi::udf::ForeignScalarUDF;
+
+use datafusion_regexp_extract_udf::codec::encode_config;
+use datafusion_regexp_extract_udf::ffi::module;
+use datafusion_regexp_extract_udf::{InvalidPatternMode, RegexpExtractConfig};
+
+#[tokio::test]
+async fn udf_loads_back_through_the_ffi_layer() {
+    let module = module();
+    let mut buf = Vec::new();
+    encode_config(
+        &RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::EmptyString),
+        &mut buf,
+    );
+    let config = String::from_utf8(buf).unwrap();
+    let ffi_udf = module.create_udf_with()(config.as_str().into()).unwrap();
+    let udf = ScalarUDF::new_from_impl(ForeignScalarUDF::try_from(&ffi_udf).unwrap());
+    assert_eq!(udf.name(), "regexp_extract");
+    assert_eq!(
+        extract(udf).await.unwrap(),
+        [Some("1".to_string()), Some(String::new()), None]
+    );
+
+    // the default config fails the query on the invalid pattern
+    let ffi_udf = module.create_udf()();
+    let udf = ScalarUDF::new_from_impl(ForeignScalarUDF::try_from(&ffi_udf).unwrap());
+    let err = extract(udf).await.unwrap_err();
+    assert!(err.to_string().contains("invalid regex pattern"), "{err}");
+}
+
+#[test]
+fn bad_config_is_reported() {
+    let err = module().create_udf_with()("regexp_extract/1\ncache_size=lots".into()).unwrap_err();
+    assert!(err.contains("cache_size"), "{err}");
+}
@@ -251,7 +251,7 @@ async fn negative_idx_errors() {
         .collect()
         .await
         .expect_err("should error on negative idx");
-    let msg = format!("{err:?}");
+    let msg = err.to_string();
     assert!(msg.contains("idx must be >= 0"));
 }
 
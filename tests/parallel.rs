@@ -0,0 +1,113 @@
+#![cfg(feature = "parallel")]
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, Int64Array, StringArray};
+use datafusion::arrow::compute::concat;
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::{RegexpExtractConfig, regexp_extract_udf_with};
+
+const ROWS: usize = 20_000;
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+async fn extract(cfg: RegexpExtractConfig, s: ArrayRef, i: ArrayRef) -> StringArray {
+    let ctx = SessionContext::new();
+    let f = regexp_extract_udf_with(cfg);
+    ctx.register_udf(f.clone());
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("i", i)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), lit(r"id=(\d+)-(\w+)"), col("i")])
+                .alias("out"),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let parts = batches
+        .iter()
+        .map(|b| b.column(0).as_ref())
+        .collect::<Vec<_>>();
+    concat(&parts)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap()
+        .clone()
+}
+
+#[tokio::test]
+async fn parallel_matches_sequential_with_nulls() {
+    let s = Arc::new(StringArray::from(
+        (0..ROWS)
+            .map(|r| match r % 7 {
+                0 => None,
+                1 => Some("no match here".to_string()),
+                _ => Some(format!("row id={r}-tag{}", r % 3)),
+            })
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let i = Arc::new(Int64Array::from(
+        (0..ROWS)
+            .map(|r| (r % 11 != 0).then_some((r % 3) as i64))
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+
+    let seq = extract(
+        RegexpExtractConfig::new().parallel_threshold(usize::MAX),
+        s.clone(),
+        i.clone(),
+    )
+    .await;
+    let par = extract(RegexpExtractConfig::new().parallel_threshold(1), s, i).await;
+
+    assert_eq!(par.len(), ROWS);
+    assert_eq!(par, seq);
+    assert!(par.is_null(0));
+    assert_eq!(par.value(2), "tag2");
+    assert_eq!(par.value(3), "id=3-tag0");
+}
+
+#[tokio::test]
+async fn parallel_reports_negative_idx_error() {
+    let s = Arc::new(StringArray::from(vec![Some("id=1-a"); ROWS])) as ArrayRef;
+    let i = Arc::new(Int64Array::from(
+        (0..ROWS)
+            .map(|r| Some(if r == 15_000 { -1 } else { 1 }))
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+
+    let ctx = SessionContext::new();
+    let f = regexp_extract_udf_with(RegexpExtractConfig::new().parallel_threshold(1));
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("i", i)])))
+        .unwrap();
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), lit(r"id=(\d+)"), col("i")])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("negative idx should fail the batch");
+    assert!(format!("{err}").contains("idx must be >= 0, got -1"));
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::error::RegexpExtractError;
+use datafusion_regexp_extract_udf::pattern_cache::PatternCache;
+use datafusion_regexp_extract_udf::re::BytesRegex;
+use datafusion_regexp_extract_udf::{
+    RegexpExtractConfig, SharedPatternCache, regexp_extract_config, regexp_extract_udf_with,
+};
+
+#[test]
+fn warm_reports_each_pattern_in_order() {
+    let cache = SharedPatternCache::new();
+    let results = cache.warm(&[r"(\d+)", "(unclosed", r"([a-z]+)"]);
+
+    let patterns = results
+        .iter()
+        .map(|r| r.pattern.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(patterns, [r"(\d+)", "(unclosed", r"([a-z]+)"]);
+    assert!(results[0].result.is_ok());
+    assert!(matches!(
+        results[1].result,
+        Err(RegexpExtractError::InvalidPattern { .. })
+    ));
+    assert!(results[2].result.is_ok());
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(r"(\d+)"));
+    assert!(!cache.contains("(unclosed"));
+}
+
+#[test]
+fn warm_from_file_reads_one_pattern_per_line() {
+    let path = std::env::temp_dir().join(format!("regexp_warm_{}.txt", std::process::id()));
+    std::fs::write(&path, "(\\d+)-(\\d+)\n\nkey = (\\w+)\n[\n").unwrap();
+
+    let cache = SharedPatternCache::new();
+    let results = cache.warm_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let failed = results
+        .iter()
+        .filter(|r| r.result.is_err())
+        .map(|r| r.pattern.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 3);
+    assert_eq!(failed, ["["]);
+    assert!(cache.contains("key = (\\w+)"));
+
+    assert!(cache.warm_from_file(&path).is_err());
+}
+
+#[test]
+fn warm_from_file_splits_on_nul_when_present() {
+    let path = std::env::temp_dir().join(format!("regexp_warm_nul_{}.txt", std::process::id()));
+    let verbose = "(?x)\n  (\\d+)  # id\n  -(\\d+)\n";
+    std::fs::write(&path, format!("{verbose}\0\0key = (\\w+)\0")).unwrap();
+
+    let cache = SharedPatternCache::new();
+    let results = cache.warm_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.result.is_ok()));
+    assert!(cache.contains(verbose));
+    assert!(cache.contains("key = (\\w+)"));
+}
+
+#[test]
+fn batch_cache_takes_warmed_patterns_without_compiling() {
+    let shared = SharedPatternCache::new();
+    shared.warm(&["a+"]);
+    let mut cache = PatternCache::new(4).with_shared(Some(shared));
+
+    assert!(cache.get_or_compile("a+").is_ok());
+    assert!(cache.get_or_compile("a+").is_ok());
+    assert!(cache.get_or_compile("b+").is_ok());
+    let stats = cache.stats();
+    assert_eq!(
+        (stats.hits, stats.misses, stats.shared_hits, stats.compiled),
+        (1, 2, 1, 1)
+    );
+}
+
+#[test]
+fn binary_batch_cache_takes_warmed_patterns() {
+    let shared = SharedPatternCache::new();
+    shared.warm(&["a+"]);
+    assert!(shared.get_bytes("a+").is_some());
+    let mut cache = PatternCache::<BytesRegex>::with_capacity(4).with_shared(Some(shared));
+
+    assert!(cache.get_or_compile("a+").is_ok());
+    assert!(cache.get_or_compile("b+").is_ok());
+    let stats = cache.stats();
+    assert_eq!((stats.shared_hits, stats.compiled), (1, 1));
+}
+
+#[tokio::test]
+async fn udf_uses_the_warmed_cache() {
+    let ctx = SessionContext::new();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("s", DataType::Utf8, true),
+        Field::new("p", DataType::Utf8, true),
+    ]));
+    let s = Arc::new(StringArray::from(vec!["100-200", "foo=bar", "x"])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![
+        r"(\d+)-(\d+)",
+        r"(\w+)=(\w+)",
+        r"(\d+)",
+    ])) as ArrayRef;
+    let batch = RecordBatch::try_new(schema.clone(), vec![s, p]).unwrap();
+    let table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+    ctx.register_table("t", Arc::new(table)).unwrap();
+
+    let shared = SharedPatternCache::new();
+    let warmed = shared.warm(&[r"(\d+)-(\d+)", r"(\w+)=(\w+)"]);
+    assert!(warmed.iter().all(|r| r.result.is_ok()));
+    let f = regexp_extract_udf_with(RegexpExtractConfig::new().pattern_cache(shared.clone()));
+    let cfg = regexp_extract_config(&f).unwrap();
+    assert!(cfg.pattern_cache.as_ref().is_some_and(|c| c.len() == 2));
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), col("p"), lit(2_i64)])
+                .alias("col_pat"),
+            f.call(vec![col("s"), lit(r"(\d+)-(\d+)"), lit(1_i64)])
+                .alias("scalar_pat"),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let col_pat = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let scalar_pat = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(col_pat.len(), 3);
+    assert_eq!(
+        (col_pat.value(0), col_pat.value(1), col_pat.value(2)),
+        ("200", "bar", "")
+    );
+    assert_eq!(
+        (
+            scalar_pat.value(0),
+            scalar_pat.value(1),
+            scalar_pat.value(2)
+        ),
+        ("100", "", "")
+    );
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "proto")]
+
+use datafusion::arrow::array::StringArray;
+use datafusion::logical_expr::{Expr, LogicalPlan, LogicalPlanBuilder};
+use datafusion::physical_expr::ScalarFunctionExpr;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::prelude::{CsvReadOptions, SessionContext, col, lit};
+use datafusion_proto::bytes::{
+    logical_plan_from_bytes_with_extension_codec, logical_plan_to_bytes_with_extension_codec,
+    physical_plan_from_bytes_with_extension_codec, physical_plan_to_bytes_with_extension_codec,
+};
+
+use datafusion_regexp_extract_udf::codec::RegexpExtractCodec;
+use datafusion_regexp_extract_udf::{
+    InvalidPatternMode, RegexpExtractConfig, regexp_extract_config, regexp_extract_udf_with,
+};
+
+fn cfg() -> RegexpExtractConfig {
+    RegexpExtractConfig::new()
+        .cache_size(7)
+        .invalid_pattern_mode(InvalidPatternMode::EmptyString)
+        .span_byte_offsets(true)
+}
+
+fn plan() -> LogicalPlan {
+    let f = regexp_extract_udf_with(cfg());
+    LogicalPlanBuilder::values(vec![
+        vec![lit("a1"), lit(r"(\d)")],
+        vec![lit("b2"), lit("(")],
+    ])
+    .unwrap()
+    .project(vec![
+        f.call(vec![col("column1"), col("column2"), lit(1_i64)])
+            .alias("x"),
+    ])
+    .unwrap()
+    .build()
+    .unwrap()
+}
+
+fn logical_udf_config(plan: &LogicalPlan) -> RegexpExtractConfig {
+    let LogicalPlan::Projection(p) = plan else {
+        panic!("expected a projection, got {plan}");
+    };
+    let Expr::Alias(a) = &p.expr[0] else {
+        panic!("expected an alias");
+    };
+    let Expr::ScalarFunction(f) = a.expr.as_ref() else {
+        panic!("expected a function call");
+    };
+    regexp_extract_config(&f.func).unwrap().clone()
+}
+
+#[tokio::test]
+async fn logical_plan_round_trip_keeps_the_config() {
+    // the receiving context has no regexp_extract registered at all
+    let ctx = SessionContext::new();
+    let bytes = logical_plan_to_bytes_with_extension_codec(&plan(), &RegexpExtractCodec).unwrap();
+    let back =
+        logical_plan_from_bytes_with_extension_codec(&bytes, &ctx, &RegexpExtractCodec).unwrap();
+    assert_eq!(logical_udf_config(&back).entries(), cfg().entries());
+
+    let batches = ctx
+        .execute_logical_plan(back)
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    // the invalid pattern gives "" under EmptyString instead of failing the query
+    assert_eq!(out.iter().collect::<Vec<_>>(), [Some("1"), Some("")]);
+}
+
+#[tokio::test]
+async fn physical_plan_round_trip_keeps_the_config() {
+    // in-memory scans have no protobuf form, so read the same rows from a file
+    let path = std::env::temp_dir().join(format!("regexp_proto_{}.csv", std::process::id()));
+    std::fs::write(&path, "column1,column2\na1,(\\d)\nb2,(\n").unwrap();
+    let ctx = SessionContext::new();
+    ctx.register_csv("t", path.to_str().unwrap(), CsvReadOptions::new())
+        .await
+        .unwrap();
+    let f = regexp_extract_udf_with(cfg());
+    let physical = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![
+            col("column1"),
+            col("column2"),
+            lit(1_i64),
+        ])])
+        .unwrap()
+        .create_physical_plan()
+        .await
+        .unwrap();
+    let bytes = physical_plan_to_bytes_with_extension_codec(physical, &RegexpExtractCodec).unwrap();
+    let back =
+        physical_plan_from_bytes_with_extension_codec(&bytes, &ctx, &RegexpExtractCodec).unwrap();
+
+    let projection = back.as_any().downcast_ref::<ProjectionExec>().unwrap();
+    let f = projection.expr()[0]
+        .0
+        .as_any()
+        .downcast_ref::<ScalarFunctionExpr>()
+        .unwrap();
+    let back_cfg = regexp_extract_config(f.fun()).unwrap();
+    assert_eq!(back_cfg.entries(), cfg().entries());
+
+    let batches = datafusion::physical_plan::collect(back, ctx.task_ctx())
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(out.iter().collect::<Vec<_>>(), [Some("1"), Some("")]);
+}
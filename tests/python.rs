@@ -0,0 +1,86 @@
+#![cfg(feature = "python")]
+
+mod common;
+
+use common::extract;
+use datafusion::logical_expr::ScalarUDF;
+use datafusion_ffi::udf::{FFI_ScalarUDF, ForeignScalarUDF};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyCapsule, PyDict};
+
+use datafusion_regexp_extract_udf::python::register;
+
+/// Calls `regexp_extract_udf(args)` on a module set up with `register` the way Python code
+/// would and unwraps the capsule it exposes, as `datafusion-python`'s `udf()` does.
+fn udf_from_python(args: &str) -> PyResult<ScalarUDF> {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "datafusion_regexp_extract_udf")?;
+        register(&module)?;
+        let locals = PyDict::new(py);
+        locals.set_item("m", module)?;
+        let code = std::ffi::CString::new(format!("m.regexp_extract_udf({args})")).unwrap();
+        let obj = py.eval(&code, None, Some(&locals))?;
+
+        let capsule = obj.call_method0("__datafusion_scalar_udf__")?;
+        let capsule = capsule.downcast::<PyCapsule>()?;
+        assert_eq!(
+            capsule.name()?.unwrap().to_str().unwrap(),
+            "datafusion_scalar_udf"
+        );
+        let ffi_udf = unsafe { capsule.reference::<FFI_ScalarUDF>() };
+        let udf = ForeignScalarUDF::try_from(ffi_udf).unwrap();
+        Ok(ScalarUDF::new_from_impl(udf))
+    })
+}
+
+#[tokio::test]
+async fn kwargs_configure_the_capsule_udf() {
+    let udf = udf_from_python(r#"invalid_pattern_mode="empty_string", cache_size=8"#).unwrap();
+    assert_eq!(udf.name(), "regexp_extract");
+    assert_eq!(
+        extract(udf).await.unwrap(),
+        vec![Some("1".to_string()), Some(String::new()), None]
+    );
+
+    let udf = udf_from_python("").unwrap();
+    let err = extract(udf).await.unwrap_err();
+    assert!(err.to_string().contains("invalid regex pattern"), "{err}");
+}
+
+#[tokio::test]
+async fn config_dict_is_applied_and_kwargs_override_it() {
+    let udf =
+        udf_from_python(r#"{"invalid_pattern_mode": "empty_string", "cache_size": 8}"#).unwrap();
+    assert_eq!(
+        extract(udf).await.unwrap(),
+        vec![Some("1".to_string()), Some(String::new()), None]
+    );
+
+    let udf = udf_from_python(
+        r#"config={"invalid_pattern_mode": "empty_string"}, invalid_pattern_mode="error""#,
+    )
+    .unwrap();
+    assert!(extract(udf).await.is_err());
+
+    let err = udf_from_python(r#"{"cache_size": -1}"#).unwrap_err();
+    Python::with_gil(|py| assert!(err.is_instance_of::<PyValueError>(py)));
+}
+
+#[test]
+fn bool_kwargs_map_to_config_values() {
+    assert!(udf_from_python("span_byte_offsets=True").is_ok());
+}
+
+#[test]
+fn bad_kwargs_raise_value_error() {
+    for kwargs in [
+        "cache_size=-1",
+        "engine='pcre'",
+        "invalid_pattern_mode='skip'",
+    ] {
+        let err = udf_from_python(kwargs).unwrap_err();
+        Python::with_gil(|py| assert!(err.is_instance_of::<PyValueError>(py), "{kwargs}"));
+    }
+}
@@ -0,0 +1,71 @@
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn regexp_lint(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_regexp-lint"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn parse_reports(out: &Output) -> Vec<Value> {
+    match serde_json::from_slice(&out.stdout) {
+        Ok(Value::Array(reports)) => reports,
+        other => panic!(
+            "expected a JSON array, got {other:?}: {}",
+            String::from_utf8_lossy(&out.stdout)
+        ),
+    }
+}
+
+#[test]
+fn lints_a_file() {
+    let dir = std::env::temp_dir().join(format!("regexp-lint-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("patterns.txt");
+    std::fs::write(&path, "id=(\\d+)\r\n\n^(a+)+$\n名前=(\\w+)\n").unwrap();
+
+    let out = regexp_lint(&[path.to_str().unwrap()], "");
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let reports = parse_reports(&out);
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[0]["pattern"], "id=(\\d+)");
+    assert_eq!(reports[0]["capture_groups"], 1);
+    assert_eq!(
+        reports[1]["findings"][0]["kind"],
+        "catastrophic_backtracking"
+    );
+    assert_eq!(reports[2]["pattern"], "名前=(\\w+)");
+    assert_eq!(reports[2]["compiles"], true);
+}
+
+#[test]
+fn lints_stdin() {
+    let out = regexp_lint(&["-"], "(?<=x)y\n\"tab\there\"(\n");
+    assert_eq!(out.status.code(), Some(1));
+    let reports = parse_reports(&out);
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0]["needs_fancy_regex"], true);
+    assert_eq!(reports[1]["pattern"], "\"tab\there\"(");
+    assert_eq!(reports[1]["compiles"], false);
+    assert!(reports[1]["engines"][0]["error"].is_string());
+
+    let out = regexp_lint(&[], "");
+    assert!(out.status.success());
+    assert!(parse_reports(&out).is_empty());
+}
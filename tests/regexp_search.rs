@@ -0,0 +1,307 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, Int32Array, Int64Array, LargeStringArray, StringArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::{
+    InvalidPatternMode, RegexpExtractConfig, regexp_count_udf, regexp_count_udf_with,
+    regexp_instr_udf, regexp_instr_udf_with, regexp_substr_udf, regexp_substr_udf_with,
+};
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+fn ints(batches: &[RecordBatch], col: usize) -> Vec<Option<i32>> {
+    batches[0]
+        .column(col)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap()
+        .iter()
+        .collect()
+}
+
+fn strs(batches: &[RecordBatch], col: usize) -> Vec<Option<String>> {
+    batches[0]
+        .column(col)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap()
+        .iter()
+        .map(|v| v.map(str::to_string))
+        .collect()
+}
+
+async fn unicode_ctx() -> SessionContext {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![
+        Some("Köln99"),
+        Some("東京123"),
+        Some("😀x7y8"),
+        Some("no digits"),
+        None,
+    ])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+    ctx
+}
+
+#[tokio::test]
+async fn count_over_unicode_and_nulls() {
+    let ctx = unicode_ctx().await;
+    let f = regexp_count_udf();
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), lit(r"\d")]),
+            f.call(vec![col("s"), lit(r"\d+")]),
+            // Java reports the empty match after every non-empty run
+            f.call(vec![col("s"), lit(r"\d*")]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ints(&batches, 0),
+        vec![Some(2), Some(3), Some(2), Some(0), None]
+    );
+    assert_eq!(
+        ints(&batches, 1),
+        vec![Some(1), Some(1), Some(2), Some(0), None]
+    );
+    assert_eq!(
+        ints(&batches, 2),
+        vec![Some(6), Some(4), Some(6), Some(10), None]
+    );
+}
+
+#[tokio::test]
+async fn instr_returns_utf16_positions() {
+    let ctx = unicode_ctx().await;
+    let f = regexp_instr_udf();
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), lit(r"\d+")]),
+            f.call(vec![col("s"), lit(r"(\D+)(\d)"), lit(2)]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    // the emoji is one char but two UTF-16 code units, as in Spark
+    assert_eq!(
+        ints(&batches, 0),
+        vec![Some(5), Some(3), Some(4), Some(0), None]
+    );
+    assert_eq!(
+        ints(&batches, 1),
+        vec![Some(5), Some(3), Some(4), Some(0), None]
+    );
+}
+
+#[tokio::test]
+async fn substr_is_null_for_no_or_empty_match() {
+    let ctx = unicode_ctx().await;
+    let f = regexp_substr_udf();
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), lit(r"\p{Han}+|[a-zö]+")]),
+            f.call(vec![col("s"), lit(r"\d*")]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        strs(&batches, 0),
+        vec![
+            Some("öln".to_string()),
+            Some("東京".to_string()),
+            Some("x".to_string()),
+            Some("no".to_string()),
+            None
+        ]
+    );
+    // every row starts with a non-digit, so the first `\d*` match is empty: NULL as in Spark
+    assert_eq!(strs(&batches, 1), vec![None, None, None, None, None]);
+}
+
+#[tokio::test]
+async fn large_utf8_returns_int64_and_column_patterns() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(LargeStringArray::from(vec![
+        Some("a1b22c333"),
+        Some("a1b22c333"),
+        Some("xyz"),
+        Some("xyz"),
+    ])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![
+        Some(r"\d+"),
+        Some("[a-z]"),
+        None,
+        Some("y"),
+    ])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("p", p)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            regexp_count_udf().call(vec![col("s"), col("p")]),
+            regexp_instr_udf().call(vec![col("s"), col("p")]),
+            regexp_substr_udf().call(vec![col("s"), col("p")]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let count = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(
+        count.iter().collect::<Vec<_>>(),
+        vec![Some(3), Some(3), None, Some(1)]
+    );
+    let instr = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(
+        instr.iter().collect::<Vec<_>>(),
+        vec![Some(2), Some(1), None, Some(2)]
+    );
+    assert_eq!(batches[0].column(2).data_type(), &DataType::LargeUtf8);
+    let substr = batches[0]
+        .column(2)
+        .as_any()
+        .downcast_ref::<LargeStringArray>()
+        .unwrap();
+    assert_eq!(
+        substr.iter().collect::<Vec<_>>(),
+        vec![Some("1"), Some("a"), None, Some("y")]
+    );
+}
+
+#[tokio::test]
+async fn invalid_pattern_and_negative_idx() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![Some("abc"), None])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            regexp_count_udf().call(vec![col("s"), lit("(unclosed")]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("invalid pattern should error by default");
+    assert!(format!("{err:?}").contains("invalid regex pattern"));
+
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_instr_udf().call(vec![
+            col("s"),
+            lit("(b)"),
+            lit(-1),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("negative idx should error");
+    assert!(format!("{err}").contains("idx must be >= 0, got -1"));
+
+    let cfg = RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::EmptyString);
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            regexp_count_udf_with(cfg.clone()).call(vec![col("s"), lit("(unclosed")]),
+            regexp_instr_udf_with(cfg.clone()).call(vec![col("s"), lit("(unclosed")]),
+            regexp_substr_udf_with(cfg).call(vec![col("s"), lit("(unclosed")]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(ints(&batches, 0), vec![Some(0), None]);
+    assert_eq!(ints(&batches, 1), vec![Some(0), None]);
+    assert_eq!(strs(&batches, 2), vec![None, None]);
+}
+
+#[tokio::test]
+async fn instr_group_index_above_group_count() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![Some("a1"), Some("zzz")])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+    let instr = |cfg: RegexpExtractConfig| {
+        regexp_instr_udf_with(cfg).call(vec![col("s"), lit(r"(\d)"), lit(2)])
+    };
+
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![instr(RegexpExtractConfig::new())])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("group index above the group count should error on a match");
+    assert!(
+        format!("{err}")
+            .contains("regexp_instr: regex group count is 1, but the specified group index is 2"),
+        "{err}"
+    );
+
+    // rows without a match never check the index
+    let cfg = RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::Collect);
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![instr(cfg)])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(ints(&batches, 0), vec![None, Some(0)]);
+}
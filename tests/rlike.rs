@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, BooleanArray, LargeStringArray, StringArray};
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+use datafusion::scalar::ScalarValue;
+
+use datafusion_regexp_extract_udf::{
+    InvalidPatternMode, RegexpExtractConfig, regexp_extract_udf, rlike_udf, rlike_udf_with,
+};
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+fn bools(batches: &[RecordBatch], col: usize) -> Vec<Option<bool>> {
+    batches[0]
+        .column(col)
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .unwrap()
+        .iter()
+        .collect()
+}
+
+#[tokio::test]
+async fn scalar_pattern_over_utf8_and_large_utf8() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![
+        Some("user_id=42"),
+        Some("anonymous"),
+        None,
+    ])) as ArrayRef;
+    let ls = Arc::new(LargeStringArray::from(vec![
+        Some("user_id=42"),
+        Some("anonymous"),
+        None,
+    ])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("ls", ls)])))
+        .unwrap();
+
+    let f = rlike_udf();
+    let large_pat = lit(ScalarValue::LargeUtf8(Some(r"user_id=\d+".to_string())));
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), lit(r"user_id=\d+")]),
+            f.call(vec![col("ls"), lit(r"user_id=\d+")]),
+            f.call(vec![col("s"), large_pat.clone()]),
+            f.call(vec![col("ls"), large_pat]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    for c in 0..4 {
+        assert_eq!(bools(&batches, c), vec![Some(true), Some(false), None]);
+    }
+}
+
+#[tokio::test]
+async fn column_pattern_with_nulls() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![
+        Some("abc"),
+        Some("abc"),
+        Some("abc"),
+        Some("東京123"),
+    ])) as ArrayRef;
+    let p = Arc::new(LargeStringArray::from(vec![
+        Some("^a"),
+        Some("^b"),
+        None,
+        Some(r"\p{Han}+\d"),
+    ])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("p", p)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![rlike_udf().call(vec![col("s"), col("p")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        bools(&batches, 0),
+        vec![Some(true), Some(false), None, Some(true)]
+    );
+}
+
+#[tokio::test]
+async fn empty_match_is_a_match_unlike_extract_not_empty() {
+    // `regexp_extract(s, 'x*', 0) != ''` misses empty matches; rlike does not
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![Some("abc")])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            rlike_udf().call(vec![col("s"), lit("x*")]),
+            regexp_extract_udf().call(vec![col("s"), lit("x*"), lit(0)]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(bools(&batches, 0), vec![Some(true)]);
+    let extracted = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(extracted.value(0), "");
+}
+
+#[tokio::test]
+async fn invalid_pattern_errors_or_is_false() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![Some("abc"), None])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![rlike_udf().call(vec![col("s"), lit("(unclosed")])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("invalid pattern should error by default");
+    assert!(format!("{err:?}").contains("invalid regex pattern"));
+
+    let lenient = rlike_udf_with(
+        RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::EmptyString),
+    );
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![lenient.call(vec![col("s"), lit("(unclosed")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(bools(&batches, 0), vec![Some(false), None]);
+}
@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, BooleanArray, Int32Array, StringArray};
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion::datasource::MemTable;
+use datafusion::logical_expr::Expr;
+use datafusion::prelude::{SessionConfig, SessionContext};
+
+use datafusion_regexp_extract_udf::{
+    ErrorLog, InvalidPatternMode, RegexpExtractConfig, RegexpExtractOptions, SharedPatternCache,
+    enable_session_options, regexp_count_udf, regexp_extract_config, regexp_extract_udf,
+    regexp_extract_udf_with, rlike_udf,
+};
+
+fn register(ctx: &SessionContext) {
+    let s = Arc::new(StringArray::from(vec!["a1", "b2"])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![r"(\d)", "(unclosed"])) as ArrayRef;
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("s", s.data_type().clone(), true),
+        Field::new("p", p.data_type().clone(), true),
+    ]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![s, p]).unwrap();
+    ctx.register_table(
+        "t",
+        Arc::new(MemTable::try_new(schema, vec![vec![batch]]).unwrap()),
+    )
+    .unwrap();
+}
+
+async fn extract(ctx: &SessionContext) -> datafusion::common::Result<Vec<Option<String>>> {
+    let batches = ctx
+        .sql("SELECT regexp_extract(s, p, 1) FROM t")
+        .await?
+        .collect()
+        .await?;
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    Ok(out.iter().map(|v| v.map(str::to_string)).collect())
+}
+
+#[tokio::test]
+async fn set_changes_the_mode_for_later_queries() {
+    let ctx = SessionContext::new();
+    enable_session_options(&ctx);
+    ctx.register_udf(regexp_extract_udf());
+    register(&ctx);
+
+    let err = extract(&ctx).await.expect_err("default mode is error");
+    assert!(err.to_string().contains("invalid regex pattern"), "{err}");
+
+    ctx.sql("SET regexp_extract.invalid_pattern_mode = 'empty_string'")
+        .await
+        .unwrap();
+    assert_eq!(
+        extract(&ctx).await.unwrap(),
+        vec![Some("1".to_string()), Some(String::new())]
+    );
+
+    ctx.sql("SET regexp_extract.invalid_pattern_mode = 'error'")
+        .await
+        .unwrap();
+    assert!(extract(&ctx).await.is_err());
+}
+
+#[tokio::test]
+async fn bad_values_and_keys_are_rejected() {
+    let ctx = SessionContext::new();
+    enable_session_options(&ctx);
+
+    let err = ctx
+        .sql("SET regexp_extract.invalid_pattern_mode = 'skip'")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("expected one of error"), "{err}");
+    let err = ctx
+        .sql("SET regexp_extract.binary_output = 'lossy_utf8'")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not a session option"), "{err}");
+}
+
+#[tokio::test]
+async fn session_defaults_keep_the_udf_error_log() {
+    let config = SessionConfig::new().with_option_extension(
+        RegexpExtractOptions::new().invalid_pattern_mode(InvalidPatternMode::Collect),
+    );
+    let ctx = SessionContext::new_with_config(config);
+    enable_session_options(&ctx);
+    let log = ErrorLog::new();
+    ctx.register_udf(regexp_extract_udf_with(
+        RegexpExtractConfig::new().error_log(log.clone()),
+    ));
+    register(&ctx);
+
+    assert_eq!(
+        extract(&ctx).await.unwrap(),
+        vec![Some("1".to_string()), None]
+    );
+    let errors = log.take();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].pattern, "(unclosed");
+}
+
+#[tokio::test]
+async fn session_options_keep_the_shared_pattern_cache() {
+    let ctx = SessionContext::new();
+    enable_session_options(&ctx);
+    let cache = SharedPatternCache::new();
+    cache.warm(&[r"(\d)"]);
+    ctx.register_udf(regexp_extract_udf_with(
+        RegexpExtractConfig::new().pattern_cache(cache.clone()),
+    ));
+    register(&ctx);
+    ctx.sql("SET regexp_extract.cache_size = 1").await.unwrap();
+
+    let plan = ctx
+        .sql("SELECT regexp_extract(s, p, 1) FROM t")
+        .await
+        .unwrap()
+        .into_optimized_plan()
+        .unwrap();
+    let mut configs = Vec::new();
+    for e in plan.expressions() {
+        e.apply(|e| {
+            if let Expr::ScalarFunction(f) = e {
+                configs.extend(regexp_extract_config(&f.func).cloned());
+            }
+            Ok(TreeNodeRecursion::Continue)
+        })
+        .unwrap();
+    }
+    assert_eq!(configs.len(), 1);
+    assert_eq!(configs[0].cache_size, 1);
+
+    // the rebuilt UDF holds the same cache, not a copy
+    let shared = configs[0].pattern_cache.as_ref().unwrap();
+    cache.warm(&[r"([a-z])"]);
+    assert_eq!(shared.patterns(), cache.patterns());
+}
+
+#[tokio::test]
+async fn set_applies_to_the_other_udfs() {
+    let ctx = SessionContext::new();
+    enable_session_options(&ctx);
+    ctx.register_udf(rlike_udf());
+    ctx.register_udf(regexp_count_udf());
+    register(&ctx);
+    let query = "SELECT rlike(s, p), regexp_count(s, p) FROM t";
+
+    let err = ctx.sql(query).await.unwrap().collect().await.unwrap_err();
+    assert!(err.to_string().contains("invalid regex pattern"), "{err}");
+
+    ctx.sql("SET regexp_extract.invalid_pattern_mode = 'empty_string'")
+        .await
+        .unwrap();
+    let batches = ctx.sql(query).await.unwrap().collect().await.unwrap();
+    let matched = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .unwrap();
+    assert_eq!(
+        matched.iter().collect::<Vec<_>>(),
+        [Some(true), Some(false)]
+    );
+    let counts = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(counts.iter().collect::<Vec<_>>(), [Some(1), Some(0)]);
+}
+
+#[tokio::test]
+async fn dataframes_keep_the_options_they_were_planned_with() {
+    let ctx = SessionContext::new();
+    enable_session_options(&ctx);
+    ctx.register_udf(regexp_extract_udf());
+    register(&ctx);
+
+    let before = ctx
+        .sql("SELECT regexp_extract(s, p, 1) FROM t")
+        .await
+        .unwrap();
+    ctx.sql("SET regexp_extract.invalid_pattern_mode = 'empty_string'")
+        .await
+        .unwrap();
+    assert!(before.collect().await.is_err());
+    assert!(extract(&ctx).await.is_ok());
+}
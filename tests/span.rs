@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, Int32Array, Int64Array, LargeStringArray, StringArray, StructArray,
+};
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::{
+    RegexpExtractConfig, regexp_extract_span_udf, regexp_extract_span_udf_with, regexp_instr_udf,
+};
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+fn offsets(st: &StructArray, name: &str) -> Vec<Option<i64>> {
+    st.column_by_name(name)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap()
+        .iter()
+        .collect()
+}
+
+#[tokio::test]
+async fn utf16_offsets_over_unicode_and_nulls() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![
+        Some("Köln99"),
+        Some("😀東京123"),
+        Some("no digits"),
+        None,
+    ])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_span_udf().call(vec![
+            col("s"),
+            lit(r"([^\d]+)(\d+)"),
+            lit(2),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let st = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_eq!(st.num_columns(), 3);
+    let values = st
+        .column_by_name("value")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(values.value(0), "99");
+    assert_eq!(values.value(1), "123");
+    assert_eq!(values.value(2), "");
+    // the emoji is one character but two UTF-16 code units
+    assert_eq!(offsets(st, "start"), vec![Some(4), Some(4), None, None]);
+    assert_eq!(offsets(st, "end"), vec![Some(6), Some(7), None, None]);
+    assert!(!st.is_null(2));
+    assert!(st.is_null(3));
+}
+
+#[tokio::test]
+async fn byte_offsets_and_large_utf8() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(LargeStringArray::from(vec![
+        Some("Köln=ja"),
+        Some("id=東京"),
+    ])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let f = regexp_extract_span_udf_with(RegexpExtractConfig::new().span_byte_offsets(true));
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), lit(r"=(\w+)"), lit(0_i64)])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let st = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    let values = st
+        .column_by_name("value")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<LargeStringArray>()
+        .unwrap();
+    assert_eq!(values.value(0), "=ja");
+    assert_eq!(values.value(1), "=東京");
+    assert_eq!(offsets(st, "start"), vec![Some(4), Some(2)]);
+    assert_eq!(offsets(st, "end"), vec![Some(7), Some(5)]);
+    assert_eq!(offsets(st, "byte_start"), vec![Some(5), Some(2)]);
+    assert_eq!(offsets(st, "byte_end"), vec![Some(8), Some(9)]);
+}
+
+#[tokio::test]
+async fn grouped_column_patterns_keep_row_order() {
+    // enough rows with few distinct patterns to take the grouped path
+    let rows = 512;
+    let s = Arc::new(StringArray::from(
+        (0..rows).map(|r| format!("k{r}=v{r}")).collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let p = Arc::new(StringArray::from(
+        (0..rows)
+            .map(|r| if r % 2 == 0 { r"=(v\d+)" } else { r"(k\d+)" })
+            .collect::<Vec<_>>(),
+    )) as ArrayRef;
+    let ctx = SessionContext::new();
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("p", p)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_span_udf().call(vec![
+            col("s"),
+            col("p"),
+            lit(1),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let st = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    let start = offsets(st, "start");
+    let end = offsets(st, "end");
+    for r in [0usize, 1, 10, 11, 511] {
+        let key_len = format!("k{r}").len() as i64;
+        if r % 2 == 0 {
+            assert_eq!(start[r], Some(key_len + 1));
+            assert_eq!(end[r], Some(2 * key_len + 1));
+        } else {
+            assert_eq!(start[r], Some(0));
+            assert_eq!(end[r], Some(key_len));
+        }
+    }
+}
+
+#[tokio::test]
+async fn span_start_agrees_with_regexp_instr() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec!["😀𝄞x=42", "a😀=7", "=1"])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let pat = r"=(\d+)";
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            regexp_extract_span_udf().call(vec![col("s"), lit(pat), lit(1)]),
+            regexp_instr_udf().call(vec![col("s"), lit(pat), lit(1)]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let st = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    let instr = batches[0]
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    let start = offsets(st, "start");
+    assert_eq!(start, vec![Some(6), Some(4), Some(1)]);
+    for (r, start) in start.iter().enumerate() {
+        assert_eq!(start.map(|v| v + 1), Some(instr.value(r) as i64));
+    }
+    assert_eq!(offsets(st, "end"), vec![Some(8), Some(5), Some(2)]);
+}
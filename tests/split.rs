@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, Int32Array, LargeStringArray, ListArray, StringArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::{
+    InvalidPatternMode, RegexpExtractConfig, split_udf, split_udf_with,
+};
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+fn lists(batches: &[RecordBatch], col: usize) -> Vec<Option<Vec<String>>> {
+    let list = batches[0]
+        .column(col)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    (0..list.len())
+        .map(|i| {
+            (!list.is_null(i)).then(|| {
+                let v = list.value(i);
+                match v.data_type() {
+                    DataType::LargeUtf8 => v
+                        .as_any()
+                        .downcast_ref::<LargeStringArray>()
+                        .unwrap()
+                        .iter()
+                        .map(|s| s.unwrap().to_string())
+                        .collect(),
+                    _ => v
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .unwrap()
+                        .iter()
+                        .map(|s| s.unwrap().to_string())
+                        .collect(),
+                }
+            })
+        })
+        .collect()
+}
+
+fn v(parts: &[&str]) -> Option<Vec<String>> {
+    Some(parts.iter().map(|s| s.to_string()).collect())
+}
+
+#[tokio::test]
+async fn spark_limit_semantics() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![
+        Some("oneAtwoBthreeC"),
+        Some("a,b,,c,,"),
+        None,
+    ])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let f = split_udf();
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), lit("[ABC,]")]),
+            f.call(vec![col("s"), lit("[ABC,]"), lit(2)]),
+            // Spark treats 0 like -1: trailing empty strings are kept
+            f.call(vec![col("s"), lit("[ABC,]"), lit(0)]),
+            f.call(vec![col("s"), lit("[ABC,]"), lit(1_i64)]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    let all = vec![
+        v(&["one", "two", "three", ""]),
+        v(&["a", "b", "", "c", "", ""]),
+        None,
+    ];
+    assert_eq!(lists(&batches, 0), all);
+    assert_eq!(
+        lists(&batches, 1),
+        vec![v(&["one", "twoBthreeC"]), v(&["a", "b,,c,,"]), None]
+    );
+    assert_eq!(lists(&batches, 2), all);
+    assert_eq!(
+        lists(&batches, 3),
+        vec![v(&["oneAtwoBthreeC"]), v(&["a,b,,c,,"]), None]
+    );
+}
+
+#[tokio::test]
+async fn unicode_empty_pattern_and_column_inputs() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(LargeStringArray::from(vec![
+        Some("Köln😀東京"),
+        Some("東京 123  x"),
+        Some("a-b"),
+        Some("a-b"),
+    ])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![
+        Some(""),
+        Some(r"\s+"),
+        None,
+        Some("-"),
+    ])) as ArrayRef;
+    let l = Arc::new(Int32Array::from(vec![Some(3), Some(-1), Some(-1), None])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("p", p), ("l", l)])))
+        .unwrap();
+
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            split_udf().call(vec![col("s"), col("p"), col("l")]),
+            split_udf().call(vec![col("s"), lit("")]),
+        ])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        batches[0].column(0).data_type(),
+        &DataType::List(Arc::new(Field::new_list_field(DataType::LargeUtf8, true)))
+    );
+    assert_eq!(
+        lists(&batches, 0),
+        vec![
+            v(&["K", "ö", "ln😀東京"]),
+            v(&["東京", "123", "x"]),
+            None,
+            None
+        ]
+    );
+    assert_eq!(
+        lists(&batches, 1)[0],
+        v(&["K", "ö", "l", "n", "😀", "東", "京"])
+    );
+}
+
+#[tokio::test]
+async fn invalid_pattern_errors_or_returns_input() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![Some("a,b"), None])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![split_udf().call(vec![col("s"), lit("(unclosed")])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("invalid pattern should error by default");
+    assert!(format!("{err:?}").contains("invalid regex pattern"));
+
+    let lenient = split_udf_with(
+        RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::EmptyString),
+    );
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![lenient.call(vec![col("s"), lit("(unclosed")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(lists(&batches, 0), vec![v(&["a,b"]), None]);
+}
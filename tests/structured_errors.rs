@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::DataFusionError;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::{SessionContext, col, lit};
+
+use datafusion_regexp_extract_udf::error::RegexpExtractError;
+use datafusion_regexp_extract_udf::{re, regexp_extract_udf};
+
+fn memtable(cols: Vec<(&str, ArrayRef)>) -> MemTable {
+    let fields = cols
+        .iter()
+        .map(|(n, a)| Field::new(*n, a.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = cols.into_iter().map(|(_, a)| a).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+    MemTable::try_new(schema, vec![vec![batch]]).unwrap()
+}
+
+async fn run_error(s: ArrayRef, p: ArrayRef) -> DataFusionError {
+    let ctx = SessionContext::new();
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s), ("p", p)])))
+        .unwrap();
+    ctx.table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_udf().call(vec![
+            col("s"),
+            col("p"),
+            lit(0),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("invalid pattern should fail the batch")
+}
+
+#[tokio::test]
+async fn invalid_pattern_is_downcastable() {
+    let s = Arc::new(StringArray::from(vec!["abc", "abc"])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec!["b", "ab[c"])) as ArrayRef;
+    let err = run_error(s, p).await;
+
+    let e = RegexpExtractError::from_datafusion(&err).expect("RegexpExtractError inside");
+    assert_eq!(e.row(), Some(1));
+    assert_eq!(e.pattern(), Some("ab[c"));
+    assert_eq!(e.engine(), Some(re::ENGINE));
+    assert!(matches!(
+        e,
+        RegexpExtractError::AtRow { source, .. }
+            if matches!(**source, RegexpExtractError::InvalidPattern { .. })
+    ));
+}
+
+#[cfg(not(feature = "fancy-regex"))]
+#[tokio::test]
+async fn diagnostic_carets_the_syntax_error() {
+    let s = Arc::new(StringArray::from(vec!["abc"])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec!["ab[c"])) as ArrayRef;
+    let err = run_error(s, p).await;
+
+    let e = RegexpExtractError::from_datafusion(&err).unwrap();
+    assert_eq!(e.span(), Some(2..3));
+    assert_eq!(e.caret().as_deref(), Some("ab[c\n  ^"));
+
+    let d = err.diagnostic().expect("diagnostic attached");
+    assert!(d.message.contains("invalid regex pattern"));
+    assert_eq!(d.notes[0].message, "ab[c\n  ^");
+    assert!(d.helps[0].message.contains("`regex`"));
+}
+
+#[cfg(not(feature = "fancy-regex"))]
+#[tokio::test]
+async fn caret_counts_characters_and_binary_engine() {
+    // the span is in bytes, the caret in characters
+    let s = Arc::new(datafusion::arrow::array::BinaryArray::from(vec![
+        b"abc".as_slice(),
+    ])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec!["é+x{2,1}"])) as ArrayRef;
+    let err = run_error(s, p).await;
+
+    let e = RegexpExtractError::from_datafusion(&err).unwrap();
+    assert_eq!(e.engine(), Some(re::BYTES_ENGINE));
+    assert_eq!(e.span(), Some(4..9));
+    assert_eq!(e.caret().as_deref(), Some("é+x{2,1}\n   ^^^^^"));
+}
+
+#[tokio::test]
+async fn row_errors_without_span_have_no_diagnostic() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec!["abc"])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+    let err = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![regexp_extract_udf().call(vec![
+            col("s"),
+            lit("b"),
+            lit(-2),
+        ])])
+        .unwrap()
+        .collect()
+        .await
+        .expect_err("negative idx");
+
+    assert!(err.diagnostic().is_none());
+    let e = RegexpExtractError::from_datafusion(&err).unwrap();
+    assert_eq!(e.row(), Some(0));
+    assert!(e.pattern().is_none());
+    assert!(matches!(
+        e,
+        RegexpExtractError::AtRow { source, .. }
+            if matches!(**source, RegexpExtractError::NegativeIndex(-2))
+    ));
+}
+
+#[test]
+fn arrow_errors_stay_arrow_errors() {
+    use datafusion::arrow::error::ArrowError;
+    use datafusion_regexp_extract_udf::RowErrorKind;
+
+    let e = RegexpExtractError::from(ArrowError::ComputeError("offset overflow".into()));
+    assert_eq!(e.kind(), RowErrorKind::Other);
+    let err = DataFusionError::from(e);
+    assert!(
+        matches!(&err, DataFusionError::ArrowError(e, None) if e.to_string().contains("offset overflow")),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "substrait")]
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::logical_expr::{Expr, LogicalPlan};
+use datafusion::prelude::{SessionContext, col, lit};
+use datafusion_substrait::substrait::proto::expression::RexType;
+use datafusion_substrait::substrait::proto::rel::RelType;
+use datafusion_substrait::substrait::proto::{FunctionOption, plan_rel};
+
+use datafusion_regexp_extract_udf::substrait::{
+    EXTENSION_URI, from_substrait_plan, to_substrait_plan,
+};
+use datafusion_regexp_extract_udf::{
+    ErrorLog, InvalidPatternMode, RegexpExtractConfig, regexp_extract_config,
+    regexp_extract_udf_with,
+};
+
+fn ctx() -> SessionContext {
+    let ctx = SessionContext::new();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("s", DataType::Utf8, true),
+        Field::new("p", DataType::Utf8, true),
+    ]));
+    let s = Arc::new(StringArray::from(vec!["a1", "b22", "c"])) as ArrayRef;
+    let p = Arc::new(StringArray::from(vec![r"(\d+)", "(", r"(\d+)"])) as ArrayRef;
+    let batch = RecordBatch::try_new(schema.clone(), vec![s, p]).unwrap();
+    let table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+    ctx.register_table("t", Arc::new(table)).unwrap();
+    ctx
+}
+
+async fn plan_for(ctx: &SessionContext, cfg: RegexpExtractConfig) -> LogicalPlan {
+    let f = regexp_extract_udf_with(cfg);
+    ctx.table("t")
+        .await
+        .unwrap()
+        .select(vec![
+            f.call(vec![col("s"), col("p"), lit(1_i64)]).alias("x"),
+        ])
+        .unwrap()
+        .into_optimized_plan()
+        .unwrap()
+}
+
+fn udf_config(plan: &LogicalPlan) -> RegexpExtractConfig {
+    let LogicalPlan::Projection(p) = plan else {
+        panic!("expected a projection, got {plan}");
+    };
+    let mut e = &p.expr[0];
+    while let Expr::Alias(a) = e {
+        e = &a.expr;
+    }
+    let Expr::ScalarFunction(f) = e else {
+        panic!("expected a function call, got {e}");
+    };
+    regexp_extract_config(&f.func).unwrap().clone()
+}
+
+async fn column(ctx: &SessionContext, plan: LogicalPlan) -> Vec<Option<String>> {
+    let batches = ctx
+        .execute_logical_plan(plan)
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    let out = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    out.iter().map(|v| v.map(str::to_string)).collect()
+}
+
+#[tokio::test]
+async fn plan_round_trip_keeps_the_config() {
+    let ctx = ctx();
+    let cfg = RegexpExtractConfig::new()
+        .cache_size(256)
+        .invalid_pattern_mode(InvalidPatternMode::EmptyString);
+    let plan = plan_for(&ctx, cfg).await;
+
+    let proto = to_substrait_plan(&plan, &ctx.state()).unwrap();
+    assert_eq!(proto.extension_uris.len(), 1);
+    assert_eq!(proto.extension_uris[0].uri, EXTENSION_URI);
+    let Some(plan_rel::RelType::Root(root)) = &proto.relations[0].rel_type else {
+        panic!("expected a root relation");
+    };
+    let Some(RelType::Project(project)) = &root.input.as_ref().unwrap().rel_type else {
+        panic!("expected a projection");
+    };
+    let Some(RexType::ScalarFunction(f)) = &project.expressions[0].rex_type else {
+        panic!("expected a function call");
+    };
+    let option = |name: &str, value: &str| FunctionOption {
+        name: name.to_string(),
+        preference: vec![value.to_string()],
+    };
+    assert_eq!(f.options, [option("invalid_pattern_mode", "empty_string")]);
+
+    // the consumer needs no registered UDF; cache_size is its own
+    let back = from_substrait_plan(&ctx.state(), &proto).await.unwrap();
+    let back_cfg = udf_config(&back);
+    assert_eq!(back_cfg.cache_size, 64);
+    assert_eq!(
+        back_cfg.invalid_pattern_mode,
+        InvalidPatternMode::EmptyString
+    );
+    assert_eq!(
+        column(&ctx, back).await,
+        [
+            Some("1".to_string()),
+            Some(String::new()),
+            Some(String::new())
+        ]
+    );
+}
+
+#[tokio::test]
+async fn consumer_keeps_the_registered_error_log() {
+    let producer = ctx();
+    let plan = plan_for(
+        &producer,
+        RegexpExtractConfig::new().invalid_pattern_mode(InvalidPatternMode::Collect),
+    )
+    .await;
+    let proto = to_substrait_plan(&plan, &producer.state()).unwrap();
+
+    let consumer = ctx();
+    let log = ErrorLog::new();
+    consumer.register_udf(regexp_extract_udf_with(
+        RegexpExtractConfig::new().error_log(log.clone()),
+    ));
+    let back = from_substrait_plan(&consumer.state(), &proto)
+        .await
+        .unwrap();
+    assert_eq!(
+        udf_config(&back).invalid_pattern_mode,
+        InvalidPatternMode::Collect
+    );
+    assert_eq!(
+        column(&consumer, back).await,
+        [Some("1".to_string()), None, Some(String::new())]
+    );
+    let rows = log.take().into_iter().map(|e| e.row).collect::<Vec<_>>();
+    assert_eq!(rows, [1]);
+}
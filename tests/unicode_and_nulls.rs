@@ -129,5 +129,42 @@ async fn negative_idx_in_column_errors() {
         .unwrap();
 
     let err = df.collect().await.expect_err("negative idx should error");
-    assert!(format!("{err:?}").contains("idx must be >= 0"));
+    assert!(format!("{err}").contains("idx must be >= 0"));
+}
+
+#[tokio::test]
+async fn null_scalar_idx_gives_null() {
+    let ctx = SessionContext::new();
+    let s = Arc::new(StringArray::from(vec![Some("a1"), Some("b2")])) as ArrayRef;
+    let one = Arc::new(StringArray::from(vec![Some("a1")])) as ArrayRef;
+    let i = Arc::new(Int32Array::from(vec![None::<i32>])) as ArrayRef;
+    ctx.register_table("t", Arc::new(memtable(vec![("s", s)])))
+        .unwrap();
+    // a one-row batch makes a NULL idx column look like a scalar
+    ctx.register_table("one", Arc::new(memtable(vec![("s", one), ("i", i)])))
+        .unwrap();
+
+    let f = regexp_extract_udf();
+    let null_idx = lit(datafusion::common::ScalarValue::Int32(None));
+    let batches = ctx
+        .table("t")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), lit(r"(\d)"), null_idx])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(batches[0].column(0).null_count(), 2);
+
+    let batches = ctx
+        .table("one")
+        .await
+        .unwrap()
+        .select(vec![f.call(vec![col("s"), lit(r"(\d)"), col("i")])])
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert!(batches[0].column(0).is_null(0));
 }